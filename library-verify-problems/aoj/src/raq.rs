@@ -1,41 +1,18 @@
 // verification-helper: https://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id=DSL_2_E
-use library::{data_structure::segtree::{lazy_segment_tree::LazySegmentTree, monoids::{MapMonoid, RangeSumMonoid}}, utils::input::Input};
-
-struct F;
-impl MapMonoid for F {
-    type M = RangeSumMonoid<u64>;
-
-    type F = u64;
-
-    fn identity_map() -> Self::F {
-        0
-    }
-
-    fn mapping(f: &Self::F, x: &<Self::M as library::data_structure::segtree::monoids::Monoid>::S) -> <Self::M as library::data_structure::segtree::monoids::Monoid>::S {
-        *f+x
-    }
-
-    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
-        *f+g
-    }
-}
-
+use library::{data_structure::segtree::{lazy_segment_tree::LazySegmentTree, monoids::RangeAddRangeSum}, utils::input::Input};
 
 fn main() {
     let mut ip = Input::new();
     let (n, q) = ip.pair();
-    let mut lseg :LazySegmentTree<F> = LazySegmentTree::new(n);
-    for i in 0..n {
-        lseg.set(i, 0);
-    }
+    let mut lseg :LazySegmentTree<RangeAddRangeSum<u64>> = LazySegmentTree::from_vec(&vec![(0, 1); n]);
     for _ in 0..q {
         if ip.next::<u8>() == 0 {
             let (l, r) = ip.pair::<usize>();
             let x = ip.next();
-            lseg.update(l..=r, &x);
+            lseg.update(l - 1..r, &x);
         } else {
             let i = ip.next::<usize>();
-            println!("{}", lseg.get(i..=i));
+            println!("{}", lseg.get_point(i - 1).0);
         }
     }
-}
\ No newline at end of file
+}
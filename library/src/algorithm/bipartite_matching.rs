@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+
+/// 二部グラフの最大マッチング (Hopcroft–Karp 法)
+/// 左側 n 頂点・右側 m 頂点・E 辺に対して $O(E \sqrt{n + m})$
+/// 最大マッチングから König の定理により最小頂点被覆・最大独立集合も求める
+pub struct BipartiteMatching {
+    g: Vec<Vec<usize>>,
+    right: usize,
+    match_l: Vec<Option<usize>>,
+    match_r: Vec<Option<usize>>,
+}
+
+impl BipartiteMatching {
+    /// left: 左側の頂点数, right: 右側の頂点数
+    pub fn new(left: usize, right: usize) -> Self {
+        Self {
+            g: vec![Vec::new(); left],
+            right,
+            match_l: vec![None; left],
+            match_r: vec![None; right],
+        }
+    }
+
+    /// 左側の頂点 u と右側の頂点 v の間に辺を追加する
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.g.len() && v < self.right);
+        self.g[u].push(v);
+    }
+
+    /// 最大マッチングを求め、(左側の頂点, 右側の頂点) の組を返す
+    pub fn max_matching(&mut self) -> Vec<(usize, usize)> {
+        let n = self.g.len();
+        let mut dist = vec![usize::MAX; n];
+        let mut iter = vec![0; n];
+        while self.bfs(&mut dist) {
+            iter.fill(0);
+            for u in 0..n {
+                if self.match_l[u].is_none() {
+                    self.dfs(u, &dist, &mut iter);
+                }
+            }
+        }
+
+        self.match_l
+            .iter()
+            .enumerate()
+            .filter_map(|(u, v)| v.map(|v| (u, v)))
+            .collect()
+    }
+
+    /// 最小頂点被覆を (左側の頂点, 右側の頂点) として返す。大きさは最大マッチングと等しい
+    pub fn min_vertex_cover(&mut self) -> (Vec<usize>, Vec<usize>) {
+        let (left, right) = self.konig();
+        (
+            (0..self.g.len()).filter(|&u| !left[u]).collect(),
+            (0..self.right).filter(|&v| right[v]).collect(),
+        )
+    }
+
+    /// 最大独立集合を (左側の頂点, 右側の頂点) として返す。最小頂点被覆の補集合
+    pub fn max_independent_set(&mut self) -> (Vec<usize>, Vec<usize>) {
+        let (left, right) = self.konig();
+        (
+            (0..self.g.len()).filter(|&u| left[u]).collect(),
+            (0..self.right).filter(|&v| !right[v]).collect(),
+        )
+    }
+
+    // マッチしていない左側の頂点から交互路で到達できる頂点の集合
+    fn konig(&mut self) -> (Vec<bool>, Vec<bool>) {
+        self.max_matching();
+        let mut left = vec![false; self.g.len()];
+        let mut right = vec![false; self.right];
+        let mut que = VecDeque::new();
+        for (u, m) in self.match_l.iter().enumerate() {
+            if m.is_none() {
+                left[u] = true;
+                que.push_back(u);
+            }
+        }
+        while let Some(u) = que.pop_front() {
+            for &v in self.g[u].iter() {
+                if right[v] {
+                    continue;
+                }
+                right[v] = true;
+                if let Some(w) = self.match_r[v] {
+                    if !left[w] {
+                        left[w] = true;
+                        que.push_back(w);
+                    }
+                }
+            }
+        }
+        (left, right)
+    }
+
+    // マッチしていない左側の頂点からの交互路の長さで層に分ける
+    // 増加路が存在すれば true を返す
+    fn bfs(&self, dist: &mut [usize]) -> bool {
+        dist.fill(usize::MAX);
+        let mut que = VecDeque::new();
+        for (u, m) in self.match_l.iter().enumerate() {
+            if m.is_none() {
+                dist[u] = 0;
+                que.push_back(u);
+            }
+        }
+        let mut found = false;
+        while let Some(u) = que.pop_front() {
+            for &v in self.g[u].iter() {
+                match self.match_r[v] {
+                    None => found = true,
+                    Some(w) if dist[w] == usize::MAX => {
+                        dist[w] = dist[u] + 1;
+                        que.push_back(w);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        found
+    }
+
+    fn dfs(&mut self, u: usize, dist: &[usize], iter: &mut [usize]) -> bool {
+        while iter[u] < self.g[u].len() {
+            let v = self.g[u][iter[u]];
+            iter[u] += 1;
+            let ok = match self.match_r[v] {
+                None => true,
+                Some(w) => dist[w] == dist[u] + 1 && self.dfs(w, dist, iter),
+            };
+            if ok {
+                self.match_l[u] = Some(v);
+                self.match_r[v] = Some(u);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bipartite_matching() {
+        let mut bm = BipartiteMatching::new(4, 3);
+        for &(u, v) in [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (2, 2)].iter() {
+            bm.add_edge(u, v);
+        }
+        let matching = bm.max_matching();
+        assert_eq!(matching.len(), 3);
+        let mut used = [false; 3];
+        for &(_, v) in matching.iter() {
+            assert!(!used[v]);
+            used[v] = true;
+        }
+
+        let (cl, cr) = bm.min_vertex_cover();
+        assert_eq!(cl.len() + cr.len(), 3);
+        for &(u, v) in [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (2, 2)].iter() {
+            assert!(cl.contains(&u) || cr.contains(&v));
+        }
+        let (il, ir) = bm.max_independent_set();
+        assert_eq!(il.len() + ir.len(), 4);
+        for &(u, v) in [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (2, 2)].iter() {
+            assert!(!(il.contains(&u) && ir.contains(&v)));
+        }
+    }
+}
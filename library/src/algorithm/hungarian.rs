@@ -0,0 +1,106 @@
+use std::ops::Neg;
+
+use crate::utils::integer::Integer;
+
+/// 割当問題をハンガリアン法で解く
+/// n x m (n <= m) のコスト行列に対し、各行に相異なる列を 1 つずつ割り当てたときの
+/// コストの総和の最小値と、各行に割り当てた列を返す。$O(n^2 m)$
+/// 重みの最大化をしたい場合はコストの符号を反転して渡す
+/// ポテンシャルが負になるので T は符号付き整数に限る。
+/// また未確定の値を T::inf() (= T::MAX >> 2) で表すため、被約コスト (コストの最大値 - 最小値程度) は
+/// T::inf() 未満でなければならない (i32 なら約 5.4e8、i64 なら約 2.3e18)
+pub fn hungarian<T: Integer + Neg<Output = T>>(cost: &[Vec<T>]) -> (T, Vec<usize>) {
+    let n = cost.len();
+    if n == 0 {
+        return (T::zero(), vec![]);
+    }
+    let m = cost[0].len();
+    assert!(n <= m);
+    let inf = T::inf();
+
+    // 1-indexed。u, v は行・列のポテンシャル、p[j] は列 j に割り当てた行 (0 は未割当)
+    let mut u = vec![T::zero(); n + 1];
+    let mut v = vec![T::zero(); m + 1];
+    let mut p = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut minv = vec![inf; m + 1];
+        let mut used = vec![false; m + 1];
+        // 行 i から交互路を伸ばし、未割当の列に届くまでポテンシャルを調整する
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = inf;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                if cur < minv[j] {
+                    minv[j] = cur;
+                    way[j] = j0;
+                }
+                if minv[j] < delta {
+                    delta = minv[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+        // 交互路に沿って割り当てを入れ替える
+        while j0 != 0 {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    let total = assignment
+        .iter()
+        .enumerate()
+        .fold(T::zero(), |acc, (i, &j)| acc + cost[i][j]);
+    (total, assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hungarian() {
+        let cost = vec![vec![4i64, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(hungarian(&cost), (5, vec![1, 0, 2]));
+
+        // 最大重み割当は符号を反転して解く
+        let weight = [vec![7i64, 3, 9, 1], vec![8, 6, 2, 5]];
+        let neg: Vec<Vec<i64>> = weight
+            .iter()
+            .map(|row| row.iter().map(|&w| -w).collect())
+            .collect();
+        let (total, assignment) = hungarian(&neg);
+        assert_eq!(-total, 17);
+        assert_eq!(assignment, vec![2, 0]);
+
+        assert_eq!(hungarian::<i64>(&[]), (0, vec![]));
+    }
+}
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+
+use crate::{algorithm::max_flow::MaxFlow, utils::integer::Integer};
+
+/*
+ * 流量の下限つき最大流 / 最小流 / 循環流
+ * 辺 (u, v, lower, upper) は容量 upper - lower の辺に置き換え、
+ * 下限分の流量を超頂点 S' → v, u → T' の辺で補う。
+ * S' から T' へ下限の総和だけ流せれば実行可能解が存在する
+*/
+
+/// 各辺に流量の下限と上限を持つネットワーク
+pub struct LowerBoundedFlow<T> {
+    n: usize,
+    // (u, v, lower, upper)
+    edges: Vec<(usize, usize, T, T)>,
+}
+
+impl<T: Integer> LowerBoundedFlow<T> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            edges: Vec::new(),
+        }
+    }
+
+    /// 頂点uから頂点vへ流量が lower 以上 upper 以下の辺を追加し、辺のidを返す
+    pub fn add_edge(&mut self, u: usize, v: usize, lower: T, upper: T) -> usize {
+        assert!(u < self.n && v < self.n);
+        assert!(T::zero() <= lower && lower <= upper);
+        self.edges.push((u, v, lower, upper));
+        self.edges.len() - 1
+    }
+
+    /// 全頂点で流量保存則を満たす循環流が存在すれば、各辺の流量を返す
+    pub fn circulation(&self) -> Option<Vec<T>> {
+        let (flow, _) = self.feasible(None)?;
+        Some(self.assignment(&flow))
+    }
+
+    /// s から t への流れ (s, t 以外で流量保存) のうち流量最大のものを求め、
+    /// (流量, 各辺の流量) を返す。実行可能解がなければ None
+    pub fn max_flow(&self, s: usize, t: usize) -> Option<(T, Vec<T>)> {
+        let (mut flow, ts) = self.feasible(Some((s, t)))?;
+        let base = self.release(&mut flow, ts);
+        let extra = flow.flow_between(s, t, T::MAX);
+        Some((base + extra, self.assignment(&flow)))
+    }
+
+    /// s から t への流れ (s, t 以外で流量保存) のうち流量最小のものを求め、
+    /// (流量, 各辺の流量) を返す。実行可能解がなければ None
+    pub fn min_flow(&self, s: usize, t: usize) -> Option<(T, Vec<T>)> {
+        let (mut flow, ts) = self.feasible(Some((s, t)))?;
+        let base = self.release(&mut flow, ts);
+        // 余分な流量を t から s へ押し戻す
+        let back = flow.flow_between(t, s, base);
+        Some((base - back, self.assignment(&flow)))
+    }
+
+    // 下限を満たす流れを 1 つ求める。s_t があれば t → s に容量無限の辺を張り、その id も返す
+    fn feasible(&self, s_t: Option<(usize, usize)>) -> Option<(MaxFlow<T>, Option<usize>)> {
+        let (super_s, super_t) = (self.n, self.n + 1);
+        let mut flow = MaxFlow::new(self.n + 2, Some((super_s, super_t)));
+        // 各頂点に下限分だけ入ってくる量・出ていく量
+        let mut in_lower = vec![T::zero(); self.n];
+        let mut out_lower = vec![T::zero(); self.n];
+        for &(u, v, lower, upper) in self.edges.iter() {
+            flow.add_edge(u, v, upper - lower);
+            out_lower[u] += lower;
+            in_lower[v] += lower;
+        }
+        let ts = s_t.map(|(s, t)| flow.add_edge(t, s, T::inf()));
+
+        let mut required = T::zero();
+        for v in 0..self.n {
+            match in_lower[v].cmp(&out_lower[v]) {
+                Ordering::Greater => {
+                    flow.add_edge(super_s, v, in_lower[v] - out_lower[v]);
+                    required += in_lower[v] - out_lower[v];
+                }
+                Ordering::Less => {
+                    flow.add_edge(v, super_t, out_lower[v] - in_lower[v]);
+                }
+                Ordering::Equal => {}
+            }
+        }
+
+        if flow.execute() == required {
+            Some((flow, ts))
+        } else {
+            None
+        }
+    }
+
+    // t → s の辺を取り除き、その辺に流れていた量 (= 現在の s-t 流量) を返す
+    fn release(&self, flow: &mut MaxFlow<T>, ts: Option<usize>) -> T {
+        let ts = ts.unwrap();
+        let base = flow.get_edge(ts).flow;
+        flow.change_edge(ts, T::zero(), T::zero());
+        base
+    }
+
+    fn assignment(&self, flow: &MaxFlow<T>) -> Vec<T> {
+        self.edges
+            .iter()
+            .enumerate()
+            .map(|(id, &(_, _, lower, _))| flow.get_edge(id).flow + lower)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(net: &LowerBoundedFlow<i64>, flows: &[i64], s_t: Option<(usize, usize)>) -> i64 {
+        let mut balance = vec![0; net.n];
+        for (&(u, v, lower, upper), &f) in net.edges.iter().zip(flows) {
+            assert!(lower <= f && f <= upper);
+            balance[u] -= f;
+            balance[v] += f;
+        }
+        for (v, &b) in balance.iter().enumerate() {
+            if s_t.is_none_or(|(s, t)| v != s && v != t) {
+                assert_eq!(b, 0);
+            }
+        }
+        s_t.map_or(0, |(_, t)| balance[t])
+    }
+
+    #[test]
+    fn test_lower_bounded_flow() {
+        let mut net = LowerBoundedFlow::new(4);
+        net.add_edge(0, 1, 1, 3);
+        net.add_edge(0, 2, 0, 2);
+        net.add_edge(1, 2, 2, 4);
+        net.add_edge(1, 3, 0, 1);
+        net.add_edge(2, 3, 1, 5);
+
+        let (mx, flows) = net.max_flow(0, 3).unwrap();
+        assert_eq!(mx, 5);
+        assert_eq!(check(&net, &flows, Some((0, 3))), 5);
+
+        let (mn, flows) = net.min_flow(0, 3).unwrap();
+        assert_eq!(mn, 2);
+        assert_eq!(check(&net, &flows, Some((0, 3))), 2);
+
+        assert!(net.circulation().is_none());
+        net.add_edge(3, 0, 0, 10);
+        let flows = net.circulation().unwrap();
+        check(&net, &flows, None);
+    }
+
+    #[test]
+    fn test_lower_bounded_flow_infeasible() {
+        let mut net = LowerBoundedFlow::new(3);
+        net.add_edge(0, 1, 3, 5);
+        net.add_edge(1, 2, 0, 2);
+        assert!(net.max_flow(0, 2).is_none());
+        assert!(net.min_flow(0, 2).is_none());
+    }
+}
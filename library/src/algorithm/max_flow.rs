@@ -1,11 +1,31 @@
 use std::collections::VecDeque;
 
-use crate::utils::{chlibs::ChLibs, consts::INF, integer::Integer};
+use crate::utils::{chlibs::ChLibs, integer::Integer};
+
+#[derive(Debug, Clone, Copy)]
+struct InnerEdge<T> {
+    to: usize,
+    // 逆辺の g[to] 内での位置
+    rev: usize,
+    cap: T,
+}
+
+/// add_edge で追加した辺の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowEdge<T> {
+    pub from: usize,
+    pub to: usize,
+    pub cap: T,
+    pub flow: T,
+}
 
-/// 与えられたグラフをもとに最大流問題を解く
-/// 一般に、N頂点M辺のグラフと実数の辺容量uが与えられたときの計算量は $O(N^2 M)$となる。
+/// 与えられたグラフをもとに最大流問題を解く (Dinic 法)
+/// 隣接リストで持ち、current arc を用いる。N頂点M辺のグラフで $O(N^2 M)$となる。
+/// 流した後に辺を追加して続きから流すこともできる
 pub struct MaxFlow<T> {
-    g: Vec<Vec<T>>,
+    g: Vec<Vec<InnerEdge<T>>>,
+    // 辺 id → (始点, g[始点] 内での位置)
+    pos: Vec<(usize, usize)>,
     start: usize,
     end: usize,
 }
@@ -16,98 +36,207 @@ impl<T: Integer> MaxFlow<T> {
     /// start, end: 指定がなければ(0, n-1)とする
     pub fn new(n: usize, start_end: Option<(usize, usize)>) -> Self {
         let (start, end) = start_end.unwrap_or((0, n - 1));
+        assert!(start < n && end < n && start != end);
 
         Self {
-            g: vec![vec![T::from_i32(0); n]; n],
+            g: vec![Vec::new(); n],
+            pos: Vec::new(),
             start,
             end,
         }
     }
 
-    /// 頂点uと頂点vの間に辺容量cを追加する
-    pub fn add_edge(&mut self, u: usize, v: usize, c: T) {
-        self.g[u][v] += c;
+    /// 頂点uから頂点vへ容量cの辺を追加し、辺のidを返す
+    pub fn add_edge(&mut self, u: usize, v: usize, c: T) -> usize {
+        assert!(u < self.g.len() && v < self.g.len());
+        assert!(c >= T::zero());
+        let id = self.pos.len();
+        self.pos.push((u, self.g[u].len()));
+        let rev_u = self.g[v].len() + usize::from(u == v);
+        let rev_v = self.g[u].len();
+        self.g[u].push(InnerEdge {
+            to: v,
+            rev: rev_u,
+            cap: c,
+        });
+        self.g[v].push(InnerEdge {
+            to: u,
+            rev: rev_v,
+            cap: T::zero(),
+        });
+        id
     }
 
+    /// id 番目に追加した辺の容量と現在の流量
+    pub fn get_edge(&self, id: usize) -> FlowEdge<T> {
+        let (from, idx) = self.pos[id];
+        let e = self.g[from][idx];
+        let re = self.g[e.to][e.rev];
+        FlowEdge {
+            from,
+            to: e.to,
+            cap: e.cap + re.cap,
+            flow: re.cap,
+        }
+    }
+
+    pub fn edges(&self) -> Vec<FlowEdge<T>> {
+        (0..self.pos.len()).map(|id| self.get_edge(id)).collect()
+    }
+
+    /// id 番目に追加した辺の容量と流量を書き換える
+    pub fn change_edge(&mut self, id: usize, cap: T, flow: T) {
+        assert!(T::zero() <= flow && flow <= cap);
+        let (from, idx) = self.pos[id];
+        let e = self.g[from][idx];
+        self.g[from][idx].cap = cap - flow;
+        self.g[e.to][e.rev].cap = flow;
+    }
 
     /// 問題を実際に解く
     pub fn execute(&mut self) -> T {
-        let n = self.g.len();
-        let sum = self.g[self.start].iter().fold(T::from_i32(0), |acc, c| acc + *c);
+        self.flow_with_limit(T::MAX)
+    }
 
-        let mut dist = vec![T::from_u64(INF); n];
+    /// 流量が limit に達するまで流し、流した量を返す
+    pub fn flow_with_limit(&mut self, limit: T) -> T {
+        self.flow_between(self.start, self.end, limit)
+    }
+
+    /// start, end とは別の頂点 s から t へ、流量が limit に達するまで流す
+    pub fn flow_between(&mut self, s: usize, t: usize, limit: T) -> T {
+        assert!(s < self.g.len() && t < self.g.len() && s != t);
+        let n = self.g.len();
+        let mut flow = T::zero();
+        let mut level = vec![None; n];
+        let mut iter = vec![0; n];
 
-        loop {
-            self.bfs(&mut dist);
-            if dist[self.end] == T::from_u64(INF) {
+        while flow < limit {
+            self.bfs(s, t, &mut level);
+            if level[t].is_none() {
                 break;
             }
-
+            iter.fill(0);
             loop {
-                let path = self.dfs(&dist);
-                if path.len() <= 1 {
+                let f = self.dfs(s, t, limit - flow, &level, &mut iter);
+                if f == T::zero() {
                     break;
                 }
-
-                let mut flow = T::from_u64(INF);
-                for i in 0..path.len() - 1 {
-                    flow.chmin(self.g[path[i]][path[i + 1]]);
-                }
-
-                for i in 0..path.len() - 1 {
-                    self.g[path[i]][path[i + 1]] -= flow;
-                    self.g[path[i + 1]][path[i]] += flow;
+                flow += f;
+                if flow == limit {
+                    break;
                 }
             }
-            dist.fill(T::from_u64(INF));
         }
 
-        let d = self.g[self.start].iter().fold(T::from_i32(0), |acc, c| acc + *c);
-
-        sum - d
+        flow
     }
 
-    fn bfs(&self, dist: &mut Vec<T>) {
-        dist[self.start] = T::from_i32(0);
+    /// 最後に流した後の残余グラフで start から到達できる頂点の集合 (最小カットの start 側)
+    pub fn min_cut(&self) -> Vec<bool> {
+        let mut visited = vec![false; self.g.len()];
         let mut que = VecDeque::new();
+        visited[self.start] = true;
         que.push_back(self.start);
         while let Some(u) = que.pop_front() {
-            for (v, ci) in self.g[u].iter().enumerate() {
-                if ci <= &T::from_i32(0) || dist[v] != T::from_u64(INF) {
-                    continue;
+            for e in self.g[u].iter() {
+                if e.cap > T::zero() && !visited[e.to] {
+                    visited[e.to] = true;
+                    que.push_back(e.to);
                 }
-                dist[v] = dist[u] + T::from_i32(1);
-                que.push_back(v);
             }
         }
+        visited
     }
 
-    fn dfs(&self, dist: &Vec<T>) -> Vec<usize> {
-        let n = self.g.len();
-        let mut parent = vec![None; n];
-        let mut stk = Vec::new();
-        stk.push(self.start);
-
-        while let Some(u) = stk.pop() {
-            for (v, ci) in self.g[u].iter().enumerate() {
-                if parent[v].is_some() || ci <= &T::from_i32(0) || dist[u] + T::from_i32(1) != dist[v] {
+    fn bfs(&self, s: usize, t: usize, level: &mut [Option<usize>]) {
+        level.fill(None);
+        level[s] = Some(0);
+        let mut que = VecDeque::new();
+        que.push_back(s);
+        while let Some(u) = que.pop_front() {
+            let next = level[u].map(|d| d + 1);
+            for e in self.g[u].iter() {
+                if e.cap <= T::zero() || level[e.to].is_some() {
                     continue;
                 }
-                parent[v] = Some(u);
-                stk.push(v);
+                level[e.to] = next;
+                if e.to == t {
+                    return;
+                }
+                que.push_back(e.to);
             }
         }
+    }
 
-        let mut res = vec![];
-        res.push(self.end);
-        let mut cur = self.end;
-        while let Some(u) = parent[cur] {
-            res.push(u);
-            cur = u;
+    // u から t へ、上限 up で level に沿って流せるだけ流す
+    fn dfs(&mut self, u: usize, t: usize, up: T, level: &[Option<usize>], iter: &mut [usize]) -> T {
+        if u == t {
+            return up;
         }
+        let mut res = T::zero();
+        while iter[u] < self.g[u].len() {
+            let e = self.g[u][iter[u]];
+            if e.cap > T::zero() && level[e.to].is_some() && level[u] < level[e.to] {
+                let mut d = up - res;
+                d.chmin(e.cap);
+                let f = self.dfs(e.to, t, d, level, iter);
+                if f > T::zero() {
+                    self.g[u][iter[u]].cap -= f;
+                    self.g[e.to][e.rev].cap += f;
+                    res += f;
+                    if res == up {
+                        return res;
+                    }
+                }
+            }
+            iter[u] += 1;
+        }
+        res
+    }
+}
 
-        res.reverse();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        let mut flow = MaxFlow::new(4, None);
+        let e0 = flow.add_edge(0, 1, 2u64);
+        flow.add_edge(0, 2, 1);
+        flow.add_edge(1, 2, 1);
+        flow.add_edge(1, 3, 1);
+        let e4 = flow.add_edge(2, 3, 2);
+        assert_eq!(flow.execute(), 3);
+        assert_eq!(
+            flow.get_edge(e0),
+            FlowEdge {
+                from: 0,
+                to: 1,
+                cap: 2,
+                flow: 2
+            }
+        );
+        assert_eq!(flow.get_edge(e4).flow, 2);
+        assert_eq!(flow.min_cut(), vec![true, false, false, false]);
+        assert_eq!(flow.edges().len(), 5);
+
+        // 辺を足して続きから流す
+        flow.add_edge(0, 3, 5);
+        assert_eq!(flow.flow_with_limit(2), 2);
+        assert_eq!(flow.execute(), 3);
+        assert_eq!(flow.min_cut(), vec![true, false, false, false]);
+    }
 
-        res
+    #[test]
+    fn test_max_flow_parallel_and_limit() {
+        let mut flow = MaxFlow::new(3, Some((2, 0)));
+        flow.add_edge(2, 1, 3i64);
+        flow.add_edge(2, 1, 4);
+        flow.add_edge(1, 0, 10);
+        flow.add_edge(1, 1, 5);
+        assert_eq!(flow.flow_with_limit(5), 5);
+        assert_eq!(flow.execute(), 2);
     }
 }
@@ -0,0 +1,234 @@
+use std::{cmp::Reverse, collections::BinaryHeap, ops::Neg};
+
+use crate::utils::{chlibs::ChLibs, integer::Integer};
+
+#[derive(Debug, Clone, Copy)]
+struct InnerEdge<Cap, Cost> {
+    to: usize,
+    // 逆辺の g[to] 内での位置
+    rev: usize,
+    cap: Cap,
+    cost: Cost,
+}
+
+/// add_edge で追加した辺の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinCostFlowEdge<Cap, Cost> {
+    pub from: usize,
+    pub to: usize,
+    pub cap: Cap,
+    pub flow: Cap,
+    pub cost: Cost,
+}
+
+/// 最小費用流 (primal-dual 法)
+/// ポテンシャルで辺のコストを非負に保ち、Dijkstra 法で最短路を求めて流していく
+/// 辺のコストは非負である必要がある (逆辺やポテンシャルで負の値を扱うので Cost は符号付き整数に限る)
+/// 流量を F として $O(F (N + M) \log M)$
+pub struct MinCostFlow<Cap, Cost> {
+    g: Vec<Vec<InnerEdge<Cap, Cost>>>,
+    // 辺 id → (始点, g[始点] 内での位置)
+    pos: Vec<(usize, usize)>,
+}
+
+impl<Cap: Integer, Cost: Integer + Neg<Output = Cost>> MinCostFlow<Cap, Cost> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            g: vec![Vec::new(); n],
+            pos: Vec::new(),
+        }
+    }
+
+    /// 頂点uから頂点vへ容量cap、単位流量あたりのコストcostの辺を追加し、辺のidを返す
+    pub fn add_edge(&mut self, u: usize, v: usize, cap: Cap, cost: Cost) -> usize {
+        assert!(u < self.g.len() && v < self.g.len());
+        assert!(cap >= Cap::zero());
+        assert!(cost >= Cost::zero());
+        let id = self.pos.len();
+        self.pos.push((u, self.g[u].len()));
+        let rev_u = self.g[v].len() + usize::from(u == v);
+        let rev_v = self.g[u].len();
+        self.g[u].push(InnerEdge {
+            to: v,
+            rev: rev_u,
+            cap,
+            cost,
+        });
+        self.g[v].push(InnerEdge {
+            to: u,
+            rev: rev_v,
+            cap: Cap::zero(),
+            cost: -cost,
+        });
+        id
+    }
+
+    /// id 番目に追加した辺の容量・現在の流量・コスト
+    pub fn get_edge(&self, id: usize) -> MinCostFlowEdge<Cap, Cost> {
+        let (from, idx) = self.pos[id];
+        let e = self.g[from][idx];
+        let re = self.g[e.to][e.rev];
+        MinCostFlowEdge {
+            from,
+            to: e.to,
+            cap: e.cap + re.cap,
+            flow: re.cap,
+            cost: e.cost,
+        }
+    }
+
+    pub fn edges(&self) -> Vec<MinCostFlowEdge<Cap, Cost>> {
+        (0..self.pos.len()).map(|id| self.get_edge(id)).collect()
+    }
+
+    /// s から t へ流量 limit を上限として流せるだけ流し、(流量, コスト) を返す
+    pub fn flow(&mut self, s: usize, t: usize, limit: Cap) -> (Cap, Cost) {
+        *self.slope_with_limit(s, t, limit).last().unwrap()
+    }
+
+    /// s から t へ流せるだけ流したときの、流量とコストの関係を表す折れ線の頂点を返す
+    /// 流量 x のときの最小コストは、隣接する頂点を結ぶ線分上にある
+    pub fn slope(&mut self, s: usize, t: usize) -> Vec<(Cap, Cost)> {
+        self.slope_with_limit(s, t, Cap::MAX)
+    }
+
+    pub fn slope_with_limit(&mut self, s: usize, t: usize, limit: Cap) -> Vec<(Cap, Cost)> {
+        assert!(s < self.g.len() && t < self.g.len() && s != t);
+        let n = self.g.len();
+        let mut dual = vec![Cost::zero(); n];
+        let mut dist = vec![Cost::MAX; n];
+        let mut prev = vec![(0, 0); n];
+        let mut visited = vec![false; n];
+
+        let mut flow = Cap::zero();
+        let mut cost = Cost::zero();
+        let mut prev_unit_cost = None;
+        let mut res = vec![(flow, cost)];
+        while flow < limit {
+            if !self.dual_ref(s, t, &mut dual, &mut dist, &mut prev, &mut visited) {
+                break;
+            }
+
+            let mut c = limit - flow;
+            let mut v = t;
+            while v != s {
+                let (u, idx) = prev[v];
+                c.chmin(self.g[u][idx].cap);
+                v = u;
+            }
+            let mut v = t;
+            while v != s {
+                let (u, idx) = prev[v];
+                let rev = self.g[u][idx].rev;
+                self.g[u][idx].cap -= c;
+                self.g[v][rev].cap += c;
+                v = u;
+            }
+
+            let unit_cost = dual[t] - dual[s];
+            flow += c;
+            cost += Cost::from_u64(c.to_u64()) * unit_cost;
+            // 単位コストが変わらなければ直前の頂点は折れ線の頂点ではない
+            if prev_unit_cost == Some(unit_cost) {
+                res.pop();
+            }
+            res.push((flow, cost));
+            prev_unit_cost = Some(unit_cost);
+        }
+        res
+    }
+
+    // 被約コスト cost + dual[u] - dual[v] で s からの最短路を求め、ポテンシャルを更新する
+    // t に到達できなければ false を返す
+    fn dual_ref(
+        &self,
+        s: usize,
+        t: usize,
+        dual: &mut [Cost],
+        dist: &mut [Cost],
+        prev: &mut [(usize, usize)],
+        visited: &mut [bool],
+    ) -> bool {
+        dist.fill(Cost::MAX);
+        visited.fill(false);
+        dist[s] = Cost::zero();
+        let mut pq = BinaryHeap::new();
+        pq.push(Reverse((Cost::zero(), s)));
+        while let Some(Reverse((d, u))) = pq.pop() {
+            if visited[u] {
+                continue;
+            }
+            visited[u] = true;
+            if u == t {
+                break;
+            }
+            for (idx, e) in self.g[u].iter().enumerate() {
+                if e.cap == Cap::zero() || visited[e.to] {
+                    continue;
+                }
+                let nd = d + e.cost + dual[u] - dual[e.to];
+                if nd < dist[e.to] {
+                    dist[e.to] = nd;
+                    prev[e.to] = (u, idx);
+                    pq.push(Reverse((nd, e.to)));
+                }
+            }
+        }
+        if !visited[t] {
+            return false;
+        }
+
+        // 確定した頂点のみ更新する。未確定の頂点は dist[t] 以上離れているので、
+        // dist[t] だけずらしたことにすれば被約コストの非負性が保たれる
+        for v in 0..dual.len() {
+            if visited[v] {
+                dual[v] += dist[v] - dist[t];
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_cost_flow() {
+        let mut mcf = MinCostFlow::new(4);
+        let e0 = mcf.add_edge(0, 1, 2u32, 1i64);
+        mcf.add_edge(0, 2, 1, 2);
+        mcf.add_edge(1, 2, 1, 1);
+        mcf.add_edge(1, 3, 1, 3);
+        mcf.add_edge(2, 3, 2, 1);
+        assert_eq!(mcf.flow(0, 3, 2), (2, 6));
+        assert_eq!(mcf.get_edge(e0).flow, 1);
+
+        let mut mcf = MinCostFlow::new(4);
+        mcf.add_edge(0, 1, 2u32, 1i64);
+        mcf.add_edge(0, 2, 1, 2);
+        mcf.add_edge(1, 2, 1, 1);
+        mcf.add_edge(1, 3, 1, 3);
+        mcf.add_edge(2, 3, 2, 1);
+        // 経路 0-1-2-3 (コスト 3)、0-2-3 (コスト 3)、0-1-3 (コスト 4)
+        assert_eq!(mcf.slope(0, 3), vec![(0, 0), (2, 6), (3, 10)]);
+        let flows: Vec<_> = mcf.edges().iter().map(|e| e.flow).collect();
+        assert_eq!(flows, vec![2, 1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_min_cost_flow_assignment() {
+        // 3 人に 3 つの仕事を割り当てる
+        let cost = [[4i64, 1, 3], [2, 0, 5], [3, 2, 2]];
+        let (s, t) = (6, 7);
+        let mut mcf = MinCostFlow::new(8);
+        for (i, row) in cost.iter().enumerate() {
+            mcf.add_edge(s, i, 1u64, 0);
+            mcf.add_edge(3 + i, t, 1, 0);
+            for (j, &c) in row.iter().enumerate() {
+                mcf.add_edge(i, 3 + j, 1, c);
+            }
+        }
+        assert_eq!(mcf.flow(s, t, 10), (3, 5));
+    }
+}
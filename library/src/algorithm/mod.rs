@@ -1,3 +1,7 @@
 pub mod aho_corasick;
 pub mod binary_search;
+pub mod bipartite_matching;
+pub mod hungarian;
+pub mod lower_bounded_flow;
 pub mod max_flow;
+pub mod min_cost_flow;
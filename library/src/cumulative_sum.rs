@@ -1,4 +1,6 @@
-use crate::utils::integer::Integer;
+use std::ops::RangeBounds;
+
+use crate::utils::{integer::Integer, versatility_functions::unpack_range};
 
 #[derive(Debug)]
 pub struct CumulativeSum<T> {
@@ -48,6 +50,138 @@ where
     }
 }
 
+/// 2次元累積和。矩形和を O(1) で答える
+#[derive(Debug)]
+pub struct CumulativeSum2D<T> {
+    sum: Vec<Vec<T>>,
+}
+
+impl<T> CumulativeSum2D<T>
+where
+    T: Integer,
+{
+    pub fn new(init_vec: &[Vec<T>]) -> CumulativeSum2D<T> {
+        let h = init_vec.len();
+        let w = init_vec.first().map_or(0, |row| row.len());
+        let mut sum = vec![vec![T::zero(); w + 1]; h + 1];
+        for i in 0..h {
+            assert_eq!(init_vec[i].len(), w);
+            for j in 0..w {
+                sum[i + 1][j + 1] = sum[i][j + 1] + sum[i + 1][j] - sum[i][j] + init_vec[i][j];
+            }
+        }
+        CumulativeSum2D { sum }
+    }
+
+    // 矩形 rows x cols の総和を答える
+    pub fn get<R1, R2>(&self, rows: R1, cols: R2) -> T
+    where
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<usize>,
+    {
+        let (u, d) = unpack_range(rows, 0, self.sum.len() - 1);
+        let (l, r) = unpack_range(cols, 0, self.sum[0].len() - 1);
+        assert!(u <= d && d < self.sum.len() && l <= r && r < self.sum[0].len());
+        // 符号なし整数でも途中で負にならないよう、足してから引く
+        (self.sum[d][r] + self.sum[u][l]) - (self.sum[u][r] + self.sum[d][l])
+    }
+}
+
+/// 1次元いもす法。区間加算をためておき、build で各要素の値を求める
+#[derive(Debug)]
+pub struct Imos1D<T> {
+    diff: Vec<T>,
+}
+
+impl<T> Imos1D<T>
+where
+    T: Integer,
+{
+    pub fn new(len: usize) -> Imos1D<T> {
+        Imos1D {
+            diff: vec![T::zero(); len + 1],
+        }
+    }
+
+    // 区間rangeにxを加算する
+    pub fn add<R>(&mut self, range: R, x: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = unpack_range(range, 0, self.diff.len() - 1);
+        assert!(l <= r && r < self.diff.len());
+        self.diff[l] += x;
+        self.diff[r] -= x;
+    }
+
+    pub fn build(&self) -> Vec<T> {
+        let len = self.diff.len() - 1;
+        let mut res = Vec::with_capacity(len);
+        let mut cur = T::zero();
+        for &d in self.diff[..len].iter() {
+            cur += d;
+            res.push(cur);
+        }
+        res
+    }
+}
+
+/// 2次元いもす法。矩形加算をためておき、build で各マスの値を求める
+#[derive(Debug)]
+pub struct Imos2D<T> {
+    diff: Vec<Vec<T>>,
+}
+
+impl<T> Imos2D<T>
+where
+    T: Integer,
+{
+    pub fn new(h: usize, w: usize) -> Imos2D<T> {
+        Imos2D {
+            diff: vec![vec![T::zero(); w + 1]; h + 1],
+        }
+    }
+
+    // 矩形 rows x cols にxを加算する
+    pub fn add<R1, R2>(&mut self, rows: R1, cols: R2, x: T)
+    where
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<usize>,
+    {
+        let h = self.diff.len() - 1;
+        let w = self.diff[0].len() - 1;
+        let (u, d) = unpack_range(rows, 0, h);
+        let (l, r) = unpack_range(cols, 0, w);
+        assert!(u <= d && d <= h && l <= r && r <= w);
+        self.diff[u][l] += x;
+        self.diff[u][r] -= x;
+        self.diff[d][l] -= x;
+        self.diff[d][r] += x;
+    }
+
+    pub fn build(&self) -> Vec<Vec<T>> {
+        let h = self.diff.len() - 1;
+        let w = self.diff[0].len() - 1;
+        let mut res = vec![vec![T::zero(); w]; h];
+        for i in 0..h {
+            for j in 0..w {
+                let mut v = self.diff[i][j];
+                if i > 0 {
+                    v += res[i - 1][j];
+                }
+                if j > 0 {
+                    v += res[i][j - 1];
+                }
+                if i > 0 && j > 0 {
+                    v -= res[i - 1][j - 1];
+                }
+                res[i][j] = v;
+            }
+        }
+        res
+    }
+}
+
 mod tests {
     #[test]
     fn cumulative_sum_get() {
@@ -60,4 +194,37 @@ mod tests {
         assert_eq!(3, cs.get(1..2));
         assert_eq!(1 + 3 + 8, cs.get(0..=2));
     }
+
+    #[test]
+    fn cumulative_sum_2d_get() {
+        use super::CumulativeSum2D;
+        let v = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let cs = CumulativeSum2D::new(&v);
+        assert_eq!(21, cs.get(.., ..));
+        assert_eq!(5 + 6, cs.get(1..2, 1..3));
+        assert_eq!(2 + 5, cs.get(0..=1, 1..=1));
+        assert_eq!(0, cs.get(1..1, ..));
+
+        let cs = CumulativeSum2D::<u64>::new(&[vec![100, 0], vec![0, 1]]);
+        assert_eq!(1, cs.get(1..2, 1..2));
+        assert_eq!(100, cs.get(0..1, ..));
+        assert_eq!(101, cs.get(.., ..));
+    }
+
+    #[test]
+    fn imos_build() {
+        use super::{Imos1D, Imos2D};
+        let mut imos = Imos1D::new(5);
+        imos.add(1..4, 2);
+        imos.add(3.., 1);
+        assert_eq!(vec![0, 2, 2, 3, 1], imos.build());
+
+        let mut imos = Imos2D::new(3, 3);
+        imos.add(0..2, 0..2, 1);
+        imos.add(1.., 1.., 3);
+        assert_eq!(
+            vec![vec![1, 1, 0], vec![1, 4, 3], vec![0, 3, 3]],
+            imos.build()
+        );
+    }
 }
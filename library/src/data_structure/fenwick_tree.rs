@@ -0,0 +1,221 @@
+use std::ops::{Add, AddAssign, Neg, RangeBounds, Sub, SubAssign};
+
+use crate::{
+    misc::modint::Modint,
+    utils::{integer::Integer, versatility_functions::unpack_range},
+};
+
+/// 可換群。Fenwick Tree で区間和を差で求めるために逆元(減算)を要求する
+pub trait AbelianGroup:
+    Copy + Add<Output = Self> + AddAssign + Sub<Output = Self> + SubAssign
+{
+    fn zero() -> Self;
+}
+
+impl<T: Integer> AbelianGroup for T {
+    fn zero() -> Self {
+        <T as Integer>::zero()
+    }
+}
+
+impl<const MOD: u64> AbelianGroup for Modint<MOD> {
+    fn zero() -> Self {
+        Modint::new(0)
+    }
+}
+
+/// 一点加算・区間和を O(log n) で処理する
+#[derive(Debug, Clone)]
+pub struct FenwickTree<T> {
+    len: usize,
+    data: Vec<T>,
+}
+
+impl<T: AbelianGroup> FenwickTree<T> {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            data: vec![T::zero(); len + 1],
+        }
+    }
+
+    /// O(n) で構築する
+    pub fn from_vec(vec: &[T]) -> Self {
+        let len = vec.len();
+        let mut data = vec![T::zero(); len + 1];
+        data[1..].copy_from_slice(vec);
+        for i in 1..=len {
+            let j = i + (i & i.wrapping_neg());
+            if j <= len {
+                let v = data[i];
+                data[j] += v;
+            }
+        }
+        Self { len, data }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// a[index] += x
+    pub fn add(&mut self, index: usize, x: T) {
+        assert!(index < self.len);
+        let mut i = index + 1;
+        while i <= self.len {
+            self.data[i] += x;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// a[0..r] の和
+    fn prefix_sum(&self, r: usize) -> T {
+        let mut res = T::zero();
+        let mut i = r;
+        while i > 0 {
+            res += self.data[i];
+            i -= i & i.wrapping_neg();
+        }
+        res
+    }
+
+    pub fn sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+
+    pub fn get(&self, index: usize) -> T {
+        self.sum(index..=index)
+    }
+}
+
+impl<T: Integer> FenwickTree<T> {
+    /// a[0..=i] の和が w 以上となる最小の i を返す。存在しなければ len を返す
+    /// 全ての要素が非負であることを要求する
+    pub fn lower_bound(&self, w: T) -> usize {
+        if w <= <T as Integer>::zero() {
+            return 0;
+        }
+        let mut w = w;
+        let mut pos = 0;
+        let mut step = 1;
+        while step * 2 <= self.len {
+            step <<= 1;
+        }
+        while step > 0 {
+            if pos + step <= self.len && self.data[pos + step] < w {
+                pos += step;
+                w -= self.data[pos];
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+/// 区間加算・区間和を O(log n) で処理する
+/// 2本の Fenwick Tree で prefix(p) = b0(..p) + b1(..p) * p の形を管理する
+/// 係数に負の値が現れるので T は符号付き整数に限る
+#[derive(Debug, Clone)]
+pub struct RangeAddFenwickTree<T> {
+    len: usize,
+    b0: FenwickTree<T>,
+    b1: FenwickTree<T>,
+}
+
+impl<T: Integer + Neg<Output = T>> RangeAddFenwickTree<T> {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            b0: FenwickTree::new(len + 1),
+            b1: FenwickTree::new(len + 1),
+        }
+    }
+
+    pub fn from_vec(vec: &[T]) -> Self {
+        let len = vec.len();
+        let mut b0 = vec.to_vec();
+        b0.push(<T as Integer>::zero());
+        Self {
+            len,
+            b0: FenwickTree::from_vec(&b0),
+            b1: FenwickTree::new(len + 1),
+        }
+    }
+
+    /// a[range] += x
+    pub fn add<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        let (tl, tr) = (T::from_u64(l as u64), T::from_u64(r as u64));
+        // [l, r) に x を足すと、prefix(p) = b0(..p) + b1(..p) * p が
+        // l <= p < r で (p - l) * x、r <= p で (r - l) * x だけ増える
+        self.b0.add(l, -(x * tl));
+        self.b0.add(r, x * tr);
+        self.b1.add(l, x);
+        self.b1.add(r, -x);
+    }
+
+    /// a[0..r] の和
+    fn prefix_sum(&self, r: usize) -> T {
+        self.b0.sum(..r) + self.b1.sum(..r) * T::from_u64(r as u64)
+    }
+
+    pub fn sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+
+    pub fn get(&self, index: usize) -> T {
+        self.sum(index..=index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenwick_tree() {
+        let mut ft = FenwickTree::from_vec(&[3i64, 1, 4, 1, 5, 9, 2]);
+        assert_eq!(ft.sum(..), 25);
+        assert_eq!(ft.sum(2..5), 10);
+        ft.add(3, 10);
+        assert_eq!(ft.sum(..=3), 19);
+        assert_eq!(ft.get(3), 11);
+
+        assert_eq!(ft.lower_bound(0), 0);
+        assert_eq!(ft.lower_bound(3), 0);
+        assert_eq!(ft.lower_bound(4), 1);
+        assert_eq!(ft.lower_bound(8), 2);
+        assert_eq!(ft.lower_bound(9), 3);
+        assert_eq!(ft.lower_bound(35), 6);
+        assert_eq!(ft.lower_bound(36), 7);
+
+        let mut ft = FenwickTree::<Modint<7>>::new(3);
+        ft.add(0, Modint::new(5));
+        ft.add(2, Modint::new(4));
+        assert_eq!(ft.sum(..), Modint::new(2));
+    }
+
+    #[test]
+    fn test_range_add_fenwick_tree() {
+        let mut naive = vec![2i64, 7, 1, 8, 2, 8];
+        let mut ft = RangeAddFenwickTree::from_vec(&naive);
+        for &(l, r, x) in [(1, 4, 3), (0, 6, -2), (5, 6, 10), (2, 3, 1)].iter() {
+            ft.add(l..r, x);
+            naive[l..r].iter_mut().for_each(|v| *v += x);
+            for l in 0..naive.len() {
+                for r in l..=naive.len() {
+                    assert_eq!(ft.sum(l..r), naive[l..r].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+}
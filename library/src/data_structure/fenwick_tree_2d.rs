@@ -0,0 +1,221 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::{
+    data_structure::fenwick_tree::{AbelianGroup, FenwickTree},
+    utils::{integer::Integer, veclibs::VecLibs, versatility_functions::unpack_range},
+};
+
+/// H x W の格子に対する一点加算・矩形和を O(log H log W) で処理する
+#[derive(Debug, Clone)]
+pub struct FenwickTree2D<T> {
+    h: usize,
+    w: usize,
+    data: Vec<Vec<T>>,
+}
+
+impl<T: AbelianGroup> FenwickTree2D<T> {
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            h,
+            w,
+            data: vec![vec![T::zero(); w + 1]; h + 1],
+        }
+    }
+
+    /// a[i][j] += x
+    pub fn add(&mut self, i: usize, j: usize, x: T) {
+        assert!(i < self.h && j < self.w);
+        let mut p = i + 1;
+        while p <= self.h {
+            let mut q = j + 1;
+            while q <= self.w {
+                self.data[p][q] += x;
+                q += q & q.wrapping_neg();
+            }
+            p += p & p.wrapping_neg();
+        }
+    }
+
+    /// [0, i) x [0, j) の和
+    fn prefix_sum(&self, i: usize, j: usize) -> T {
+        let mut res = T::zero();
+        let mut p = i;
+        while p > 0 {
+            let mut q = j;
+            while q > 0 {
+                res += self.data[p][q];
+                q -= q & q.wrapping_neg();
+            }
+            p -= p & p.wrapping_neg();
+        }
+        res
+    }
+
+    /// rows x cols の矩形和
+    pub fn sum<R1, R2>(&self, rows: R1, cols: R2) -> T
+    where
+        R1: RangeBounds<usize>,
+        R2: RangeBounds<usize>,
+    {
+        let (u, d) = unpack_range(rows, 0, self.h);
+        let (l, r) = unpack_range(cols, 0, self.w);
+        assert!(u <= d && d <= self.h && l <= r && r <= self.w);
+        // 符号なし整数でも途中で負にならないよう、足してから引く
+        (self.prefix_sum(d, r) + self.prefix_sum(u, l))
+            - (self.prefix_sum(u, r) + self.prefix_sum(d, l))
+    }
+}
+
+/// 事前に登録した点に対する一点加算・矩形和を O(log^2 n) で処理する
+/// x 方向の Fenwick Tree の各ノードが、担当する点の y 座標をソートして持つ
+#[derive(Debug, Clone)]
+pub struct OfflineFenwickTree2D<K, T> {
+    xs: Vec<K>,
+    ys: Vec<Vec<K>>,
+    bits: Vec<FenwickTree<T>>,
+}
+
+impl<K: Integer, T: AbelianGroup> OfflineFenwickTree2D<K, T> {
+    /// 更新が行われうる点を全て渡して構築する
+    pub fn new(points: &[(K, K)]) -> Self {
+        let mut xs: Vec<K> = points.iter().map(|&(x, _)| x).collect();
+        xs.sort();
+        xs.dedup();
+
+        let n = xs.len();
+        let mut ys = vec![vec![]; n + 1];
+        for &(x, y) in points.iter() {
+            let mut i = xs.lower_bound(x) + 1;
+            while i <= n {
+                ys[i].push(y);
+                i += i & i.wrapping_neg();
+            }
+        }
+        for v in ys.iter_mut() {
+            v.sort();
+            v.dedup();
+        }
+        let bits = ys.iter().map(|v| FenwickTree::new(v.len())).collect();
+
+        Self { xs, ys, bits }
+    }
+
+    /// 点 (x, y) に w を加算する。(x, y) は構築時に登録されている必要がある
+    pub fn add(&mut self, x: K, y: K, w: T) {
+        let i = self.xs.lower_bound(x);
+        assert!(
+            i < self.xs.len() && self.xs[i] == x,
+            "point is not registered"
+        );
+        let mut i = i + 1;
+        while i < self.ys.len() {
+            let j = self.ys[i].lower_bound(y);
+            assert!(
+                j < self.ys[i].len() && self.ys[i][j] == y,
+                "point is not registered"
+            );
+            self.bits[i].add(j, w);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// x 座標が小さい方から xi 個の点のうち、y が y_range に含まれる点の和
+    fn prefix_sum(&self, xi: usize, y_range: (Bound<&K>, Bound<&K>)) -> T {
+        let mut res = T::zero();
+        let mut i = xi;
+        while i > 0 {
+            let (l, r) = Self::index_range(&self.ys[i], y_range);
+            if l < r {
+                res += self.bits[i].sum(l..r);
+            }
+            i -= i & i.wrapping_neg();
+        }
+        res
+    }
+
+    /// ソート済みの v のうち range に含まれる要素の添字の範囲 [l, r)
+    /// 番兵の値に変換せず添字で扱うので、K::MAX や K::MIN を含む範囲も正しく扱える
+    fn index_range(v: &[K], range: (Bound<&K>, Bound<&K>)) -> (usize, usize) {
+        let l = match range.0 {
+            Bound::Included(&s) => v.partition_point(|&a| a < s),
+            Bound::Excluded(&s) => v.partition_point(|&a| a <= s),
+            Bound::Unbounded => 0,
+        };
+        let r = match range.1 {
+            Bound::Included(&e) => v.partition_point(|&a| a <= e),
+            Bound::Excluded(&e) => v.partition_point(|&a| a < e),
+            Bound::Unbounded => v.len(),
+        };
+        (l, r)
+    }
+
+    /// x_range x y_range に含まれる点の和
+    pub fn sum<R1, R2>(&self, x_range: R1, y_range: R2) -> T
+    where
+        R1: RangeBounds<K>,
+        R2: RangeBounds<K>,
+    {
+        let (xl, xr) = Self::index_range(&self.xs, (x_range.start_bound(), x_range.end_bound()));
+        if xl >= xr {
+            return T::zero();
+        }
+        let y_range = (y_range.start_bound(), y_range.end_bound());
+        self.prefix_sum(xr, y_range) - self.prefix_sum(xl, y_range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenwick_tree_2d() {
+        let mut ft = FenwickTree2D::<i64>::new(3, 4);
+        ft.add(0, 0, 1);
+        ft.add(1, 2, 5);
+        ft.add(2, 3, -2);
+        assert_eq!(ft.sum(.., ..), 4);
+        assert_eq!(ft.sum(1.., 2..), 3);
+        assert_eq!(ft.sum(..2, ..3), 6);
+        assert_eq!(ft.sum(1..2, 0..2), 0);
+
+        let mut ft = FenwickTree2D::<u64>::new(2, 2);
+        ft.add(0, 0, 5);
+        ft.add(1, 1, 2);
+        assert_eq!(ft.sum(1..2, 1..2), 2);
+        assert_eq!(ft.sum(.., 1..), 2);
+        assert_eq!(ft.sum(.., ..), 7);
+    }
+
+    #[test]
+    fn test_offline_fenwick_tree_2d() {
+        let points = [(-5i64, 3i64), (10, -7), (1_000_000_000, 3), (10, 3), (0, 0)];
+        let mut ft = OfflineFenwickTree2D::<i64, i64>::new(&points);
+        for (i, &(x, y)) in points.iter().enumerate() {
+            ft.add(x, y, i as i64 + 1);
+        }
+        assert_eq!(ft.sum(.., ..), 15);
+        assert_eq!(ft.sum(..10, ..), 1 + 5);
+        assert_eq!(ft.sum(..=10, 3..=3), 1 + 4);
+        assert_eq!(ft.sum(0..1_000_000_000, -7..1), 2 + 5);
+        assert_eq!(ft.sum(11..20, ..), 0);
+        ft.add(10, 3, 10);
+        assert_eq!(ft.sum(10..=10, ..), 2 + 14);
+    }
+
+    #[test]
+    fn test_offline_fenwick_tree_2d_extreme() {
+        let points = [(i32::MAX, i32::MAX), (i32::MIN, 0), (0, i32::MIN)];
+        let mut ft = OfflineFenwickTree2D::<i32, i64>::new(&points);
+        for (i, &(x, y)) in points.iter().enumerate() {
+            ft.add(x, y, 1 << i);
+        }
+        assert_eq!(ft.sum(.., ..), 7);
+        assert_eq!(ft.sum(..=i32::MAX, ..=i32::MAX), 7);
+        assert_eq!(ft.sum(i32::MAX.., i32::MAX..), 1);
+        assert_eq!(ft.sum(..i32::MAX, ..), 6);
+        assert_eq!(ft.sum(..=0, ..0), 4);
+        assert_eq!(ft.sum(i32::MIN..=i32::MIN, ..), 2);
+        assert_eq!(ft.sum(0..0, ..), 0);
+    }
+}
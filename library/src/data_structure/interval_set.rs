@@ -1,4 +1,7 @@
-use std::{collections::BTreeSet, ops::RangeBounds};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeBounds,
+};
 
 use crate::utils::{chlibs::ChLibs, integer::Integer, versatility_functions::unpack_range};
 
@@ -125,6 +128,34 @@ impl<T: Integer> IntervalSet<T> {
     pub fn covered_length(&self) -> T {
         self.sum
     }
+
+    /// x を含む区間 [l, r) を返す
+    pub fn find_interval(&self, x: T) -> Option<(T, T)> {
+        let inv = self.set.range(..(x + T::from_i32(1), T::MAX)).last()?;
+        if inv.0 <= x && x < inv.1 {
+            Some(*inv)
+        } else {
+            None
+        }
+    }
+
+    /// x 以上で区間に覆われていない最小の値
+    pub fn mex_from(&self, x: T) -> T {
+        // 隣接する区間は insert 時にまとめられているので、x を含む区間の右端は覆われていない
+        self.find_interval(x).map_or(x, |inv| inv.1)
+    }
+
+    /// x 以上で区間に覆われていない最初の極大区間 [l, r) を返す
+    /// 右側に区間がなければ r = T::MAX
+    pub fn next_uncovered(&self, x: T) -> (T, T) {
+        let l = self.mex_from(x);
+        let r = self
+            .set
+            .range((l, T::MIN)..)
+            .next()
+            .map_or(T::MAX, |inv| inv.0);
+        (l, r)
+    }
 }
 
 impl<T: Integer> IntervalSet<T> {
@@ -132,3 +163,154 @@ impl<T: Integer> IntervalSet<T> {
         self.set.iter()
     }
 }
+
+/// 区間ごとに値を割り当てる range map (いわゆる ODT / Chtholly tree)
+/// 値が割り当てられた区間を、左端をキーとして (右端, 値) の形で持つ
+/// 隣接していて値の等しい区間は 1 つにまとめる
+#[derive(Debug, Clone)]
+pub struct IntervalMap<K: Integer, V: Clone + PartialEq> {
+    map: BTreeMap<K, (K, V)>,
+}
+
+impl<K: Integer, V: Clone + PartialEq> Default for IntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Integer, V: Clone + PartialEq> IntervalMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// range の値を value で上書きする
+    pub fn assign<R: RangeBounds<K>>(&mut self, range: R, value: V) {
+        let (mut l, mut r) = unpack_range(range, K::MIN, K::MAX);
+        if l >= r {
+            return;
+        }
+        self.erase(l..r);
+
+        // 左右の区間と値が等しければまとめる
+        if let Some((&pl, (pr, pv))) = self.map.range(..l).next_back() {
+            if *pr == l && *pv == value {
+                l = pl;
+                self.map.remove(&pl);
+            }
+        }
+        if let Some((nr, nv)) = self.map.get(&r) {
+            if *nv == value {
+                let nr = *nr;
+                self.map.remove(&r);
+                r = nr;
+            }
+        }
+        self.map.insert(l, (r, value));
+    }
+
+    /// range に割り当てられた値を取り除く
+    pub fn erase<R: RangeBounds<K>>(&mut self, range: R) {
+        let (l, r) = unpack_range(range, K::MIN, K::MAX);
+        if l >= r {
+            return;
+        }
+        self.split(l);
+        self.split(r);
+        let keys: Vec<K> = self.map.range(l..r).map(|(&k, _)| k).collect();
+        for k in keys {
+            self.map.remove(&k);
+        }
+    }
+
+    /// x に割り当てられた値
+    pub fn get(&self, x: K) -> Option<&V> {
+        self.find_interval(x).map(|(_, _, v)| v)
+    }
+
+    /// x を含む区間 (l, r, 値) を返す
+    pub fn find_interval(&self, x: K) -> Option<(K, K, &V)> {
+        let (&l, (r, v)) = self.map.range(..=x).next_back()?;
+        if x < *r {
+            Some((l, *r, v))
+        } else {
+            None
+        }
+    }
+
+    /// range と交わる区間を、range の内側に切り詰めて (l, r, 値) として昇順に列挙する
+    pub fn iter_range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (K, K, &V)> {
+        let (l, r) = unpack_range(range, K::MIN, K::MAX);
+        let start = self.find_interval(l).map_or(l, |(s, _, _)| s);
+        self.map
+            .range(start..)
+            .take_while(move |(&s, _)| s < r)
+            .map(move |(&s, (e, v))| (s.max(l), (*e).min(r), v))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (K, K, &V)> {
+        self.map.iter().map(|(&l, (r, v))| (l, *r, v))
+    }
+
+    // x をまたぐ区間があれば x で 2 つに分ける
+    fn split(&mut self, x: K) {
+        if let Some((l, r, v)) = self.find_interval(x) {
+            if l < x {
+                let v = v.clone();
+                self.map.insert(l, (x, v.clone()));
+                self.map.insert(x, (r, v));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_set_queries() {
+        let mut set = IntervalSet::new();
+        set.insert(2..5);
+        set.insert(5..7);
+        set.insert(10..12);
+        assert_eq!(set.find_interval(4), Some((2, 7)));
+        assert_eq!(set.find_interval(7), None);
+        assert_eq!(set.mex_from(0), 0);
+        assert_eq!(set.mex_from(3), 7);
+        assert_eq!(set.mex_from(11), 12);
+        assert_eq!(set.next_uncovered(3), (7, 10));
+        assert_eq!(set.next_uncovered(8), (8, 10));
+        assert_eq!(set.next_uncovered(10), (12, i64::MAX));
+    }
+
+    #[test]
+    fn test_interval_map() {
+        let mut map = IntervalMap::new();
+        map.assign(0..10, 'a');
+        map.assign(3..5, 'b');
+        map.assign(8..=12, 'c');
+        assert_eq!(map.get(2), Some(&'a'));
+        assert_eq!(map.get(4), Some(&'b'));
+        assert_eq!(map.get(12), Some(&'c'));
+        assert_eq!(map.get(13), None);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(0, 3, &'a'), (3, 5, &'b'), (5, 8, &'a'), (8, 13, &'c')]
+        );
+        assert_eq!(
+            map.iter_range(4..9).collect::<Vec<_>>(),
+            vec![(4, 5, &'b'), (5, 8, &'a'), (8, 9, &'c')]
+        );
+
+        map.assign(3..5, 'a');
+        assert_eq!(map.find_interval(4), Some((0, 8, &'a')));
+        map.erase(6..9);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(0, 6, &'a'), (9, 13, &'c')]
+        );
+        assert_eq!(map.iter_range(..).count(), 2);
+    }
+}
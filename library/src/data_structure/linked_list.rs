@@ -3,6 +3,7 @@ pub struct Item<T> {
     previous: Option<usize>,
     next: Option<usize>,
     val: Option<T>,
+    alive: bool,
 }
 
 impl<T> Item<T>
@@ -14,13 +15,13 @@ where
             previous: None,
             next: None,
             val: x,
+            alive: true,
         }
     }
 
-    // dummy nodeが呼ばれないことを期待
-    pub fn get(&self) -> T {
-        assert!(self.val.is_some());
-        self.val.unwrap()
+    // dummy nodeならNone
+    pub fn get(&self) -> Option<T> {
+        self.val
     }
 
     pub fn update_prev(&mut self, prev: Option<usize>) {
@@ -40,10 +41,15 @@ where
     }
 }
 
+/// 配列上に確保する双方向連結リスト
+/// index 0 は値を持たない dummy node で、リストが空でも挿入の基準点として使える
+/// 削除した node の index は次の挿入で再利用される
 pub struct LinkedList<T> {
     vec: Vec<Item<T>>,
     head: usize,
     tail: usize,
+    len: usize,
+    free: Vec<usize>,
 }
 
 impl<T> Default for LinkedList<T>
@@ -65,58 +71,304 @@ where
             vec: vec![dummy],
             head: 0,
             tail: 0,
+            len: 0,
+            free: Vec::new(),
         }
     }
 
     // idxの後ろに要素を差し込み後、生成されたNodeのindexを返却
     pub fn add_back(&mut self, idx: usize, x: T) -> usize {
-        let len = self.vec.len();
-        let mut node = Item::new(Some(x));
-        node.update_prev(Some(idx));
+        assert!(self.is_alive(idx) || idx == 0);
+        let new = self.alloc(x);
+        self.link_after(new, new, idx);
+        new
+    }
+
+    // idxの前に要素を差し込み後、生成されたNodeのindexを返却
+    pub fn add_front(&mut self, idx: usize, x: T) -> usize {
+        assert!(self.is_alive(idx) || idx == 0);
+        let new = self.alloc(x);
+        self.link_before(new, new, idx);
+        new
+    }
+
+    // idx番のnodeを削除する。indexは以降の挿入で再利用される
+    pub fn delete(&mut self, idx: usize) {
+        assert!(self.is_alive(idx), "node {} is not alive", idx);
+        self.unlink(idx, idx);
+        self.vec[idx].alive = false;
+        self.vec[idx].val = None;
+        self.free.push(idx);
+        self.len -= 1;
+    }
+
+    /// 要素数 (dummy node は含まない)
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-        if let Some(i) = self.vec[idx].next() {
-            node.update_next(Some(i));
-            self.vec[i].update_prev(Some(len));
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// idx が削除されていない要素の node か (dummy node は false)
+    pub fn is_alive(&self, idx: usize) -> bool {
+        idx != 0 && idx < self.vec.len() && self.vec[idx].alive
+    }
+
+    pub fn get(&self, idx: usize) -> Option<T> {
+        if self.is_alive(idx) {
+            self.vec[idx].get()
         } else {
-            self.tail = len;
+            None
         }
-        self.vec[idx].update_next(Some(len));
-        len
     }
 
-    // idxの前に要素を差し込み後、生成されたNodeのindexを返却
-    pub fn add_front(&mut self, idx: usize, x: T) -> usize {
-        let len = self.vec.len();
-        let mut node = Item::new(Some(x));
-        node.update_next(Some(idx));
-        if let Some(i) = self.vec[idx].previous() {
-            node.update_prev(Some(i));
-            self.vec[i].update_next(Some(len));
+    /// 先頭の要素の index
+    pub fn head(&self) -> Option<usize> {
+        self.skip_dummy(Some(self.head), true)
+    }
+
+    /// 末尾の要素の index
+    pub fn tail(&self) -> Option<usize> {
+        self.skip_dummy(Some(self.tail), false)
+    }
+
+    /// idx の次の要素の index
+    pub fn next(&self, idx: usize) -> Option<usize> {
+        self.skip_dummy(self.vec[idx].next(), true)
+    }
+
+    /// idx の前の要素の index
+    pub fn previous(&self, idx: usize) -> Option<usize> {
+        self.skip_dummy(self.vec[idx].previous(), false)
+    }
+
+    /// 先頭から (index, 値) を列挙する
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            cur: self.head(),
+            forward: true,
+        }
+    }
+
+    /// 末尾から (index, 値) を列挙する
+    pub fn iter_rev(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            cur: self.tail(),
+            forward: false,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().map(|(_, x)| x).collect()
+    }
+
+    /// idx を target の直後に移動する
+    pub fn move_after(&mut self, idx: usize, target: usize) {
+        self.splice(idx, idx, target);
+    }
+
+    /// l から next を辿って r までの区間を切り取り、target の直後に移動する
+    /// target は区間に含まれていてはいけない
+    /// 区間の妥当性を確認するため、区間の長さを k として O(k)
+    pub fn splice(&mut self, l: usize, r: usize, target: usize) {
+        assert!(self.is_alive(l) && self.is_alive(r));
+        assert!(self.is_alive(target) || target == 0);
+        assert!(
+            self.segment_excludes(l, r, target),
+            "r must be reachable from l and target must be outside [l, r]"
+        );
+        if self.vec[l].previous() == Some(target) {
+            return;
+        }
+        self.unlink(l, r);
+        self.link_after(l, r, target);
+    }
+
+    // l から next を辿って r に到達でき、その途中に target が現れないか
+    fn segment_excludes(&self, l: usize, r: usize, target: usize) -> bool {
+        let mut cur = l;
+        loop {
+            if cur == target {
+                return false;
+            }
+            if cur == r {
+                return true;
+            }
+            match self.vec[cur].next() {
+                Some(i) => cur = i,
+                None => return false,
+            }
+        }
+    }
+
+    fn alloc(&mut self, x: T) -> usize {
+        self.len += 1;
+        if let Some(idx) = self.free.pop() {
+            self.vec[idx] = Item::new(Some(x));
+            idx
         } else {
-            self.head = len;
+            self.vec.push(Item::new(Some(x)));
+            self.vec.len() - 1
         }
-        self.vec[idx].update_prev(Some(len));
-        len
     }
 
-    // idx番のnodeを削除する(といいつつ、実際には消さないのでMLEには注意)
-    pub fn delete(&mut self, idx: usize) {
-        let deleted_node = self.vec[idx];
+    // [l, r] の区間をリストから外す
+    fn unlink(&mut self, l: usize, r: usize) {
+        let prev = self.vec[l].previous();
+        let next = self.vec[r].next();
 
         // 前の処理
-        if let Some(i) = deleted_node.previous() {
-            self.vec[i].update_next(deleted_node.next());
+        if let Some(i) = prev {
+            self.vec[i].update_next(next);
         } else {
-            assert!(deleted_node.next().is_some());
-            self.head = deleted_node.next().unwrap();
+            assert!(next.is_some());
+            self.head = next.unwrap();
         }
 
         // 後ろの処理
-        if let Some(i) = deleted_node.next() {
-            self.vec[i].update_prev(deleted_node.previous());
+        if let Some(i) = next {
+            self.vec[i].update_prev(prev);
+        } else {
+            assert!(prev.is_some());
+            self.tail = prev.unwrap();
+        }
+
+        self.vec[l].update_prev(None);
+        self.vec[r].update_next(None);
+    }
+
+    // リストから外れている [l, r] を target の後ろにつなぐ
+    fn link_after(&mut self, l: usize, r: usize, target: usize) {
+        if let Some(i) = self.vec[target].next() {
+            self.vec[r].update_next(Some(i));
+            self.vec[i].update_prev(Some(r));
         } else {
-            assert!(deleted_node.previous().is_some());
-            self.tail = deleted_node.previous().unwrap();
+            self.tail = r;
         }
+        self.vec[l].update_prev(Some(target));
+        self.vec[target].update_next(Some(l));
+    }
+
+    // リストから外れている [l, r] を target の前につなぐ
+    fn link_before(&mut self, l: usize, r: usize, target: usize) {
+        if let Some(i) = self.vec[target].previous() {
+            self.vec[l].update_prev(Some(i));
+            self.vec[i].update_next(Some(l));
+        } else {
+            self.head = l;
+        }
+        self.vec[r].update_next(Some(target));
+        self.vec[target].update_prev(Some(r));
+    }
+
+    fn skip_dummy(&self, idx: Option<usize>, forward: bool) -> Option<usize> {
+        match idx {
+            Some(0) if forward => self.vec[0].next(),
+            Some(0) => self.vec[0].previous(),
+            idx => idx,
+        }
+    }
+}
+
+/// LinkedList::iter / LinkedList::iter_rev が返すイテレータ
+pub struct Iter<'a, T> {
+    list: &'a LinkedList<T>,
+    cur: Option<usize>,
+    forward: bool,
+}
+
+impl<T: Copy> Iterator for Iter<'_, T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.cur?;
+        self.cur = if self.forward {
+            self.list.next(idx)
+        } else {
+            self.list.previous(idx)
+        };
+        Some((idx, self.list.vec[idx].get().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linked_list() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.head(), None);
+        let a = list.add_back(0, 1);
+        let b = list.add_back(a, 2);
+        let c = list.add_back(b, 3);
+        let d = list.add_front(a, 0);
+        assert_eq!(list.to_vec(), vec![0, 1, 2, 3]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.head(), Some(d));
+        assert_eq!(list.tail(), Some(c));
+        assert_eq!(
+            list.iter_rev().map(|(_, x)| x).collect::<Vec<_>>(),
+            vec![3, 2, 1, 0]
+        );
+
+        list.delete(b);
+        assert!(!list.is_alive(b));
+        assert!(!list.is_alive(0));
+        assert_eq!(list.get(b), None);
+        assert_eq!(list.to_vec(), vec![0, 1, 3]);
+        let e = list.add_back(c, 4);
+        assert_eq!(e, b);
+        assert_eq!(list.to_vec(), vec![0, 1, 3, 4]);
+
+        list.move_after(d, c);
+        assert_eq!(list.to_vec(), vec![1, 3, 0, 4]);
+        list.splice(c, d, e);
+        assert_eq!(list.to_vec(), vec![1, 4, 3, 0]);
+        assert_eq!(list.tail(), Some(d));
+        list.splice(e, d, 0);
+        assert_eq!(list.to_vec(), vec![4, 3, 0, 1]);
+        assert_eq!(list.previous(a), Some(d));
+        assert_eq!(list.next(a), None);
+
+        for idx in [a, c, d, e].iter() {
+            list.delete(*idx);
+        }
+        assert!(list.is_empty());
+        assert_eq!(list.iter().count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linked_list_move_after_self() {
+        let mut list = LinkedList::new();
+        let a = list.add_back(0, 1);
+        list.add_back(a, 2);
+        list.move_after(a, a);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linked_list_splice_into_itself() {
+        let mut list = LinkedList::new();
+        let a = list.add_back(0, 1);
+        let b = list.add_back(a, 2);
+        let c = list.add_back(b, 3);
+        list.splice(a, c, b);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linked_list_splice_reversed_segment() {
+        let mut list = LinkedList::new();
+        let a = list.add_back(0, 1);
+        let b = list.add_back(a, 2);
+        let c = list.add_back(b, 3);
+        list.splice(b, a, c);
     }
 }
@@ -1,6 +1,13 @@
+pub mod fenwick_tree;
+pub mod fenwick_tree_2d;
 pub mod interval_set;
 pub mod linked_list;
 pub mod multiset;
+pub mod offline_dynamic_connectivity;
+pub mod partially_persistent_unionfind;
 pub mod potentiality_unionfind;
+pub mod rollback_unionfind;
 pub mod segtree;
+pub mod sparse_table;
+pub mod treap;
 pub mod unionfind;
@@ -1,8 +1,21 @@
-use crate::utils::integer::Integer;
+use std::{
+    cmp::Ordering,
+    ops::{Bound, RangeBounds},
+};
 
+use crate::{
+    data_structure::treap::{OrderedLink, OrderedNode},
+    utils::integer::Integer,
+};
+
+/// 多重集合
+/// キーごとの個数を treap のノードの重みとして持ち、部分木の個数の総和を使って
+/// 追加・削除・k 番目・順位の取得をいずれも期待 O(log n) で行う
+/// 部分木の総和を壊さないよう、個数の変更は add / remove からのみ行う
 #[derive(Debug, Clone)]
 pub struct MultiSet<T, U> {
-    map: std::collections::BTreeMap<T, U>,
+    root: OrderedLink<T, U>,
+    distinct: usize,
 }
 
 impl<T, U> Default for MultiSet<T, U>
@@ -21,86 +34,335 @@ where
     U: Integer,
 {
     pub fn new() -> Self {
-        use std::collections::BTreeMap;
         MultiSet {
-            map: BTreeMap::new(),
+            root: None,
+            distinct: 0,
         }
     }
 
     /* keyをsizeだけ増やして新しい値を返す */
     pub fn add(&mut self, key: T, size: U) -> U {
-        *self.map.entry(key).or_insert(U::zero()) += size;
-        *self.map.get(&key).unwrap()
+        if self.get(key).is_some() {
+            return self.modify(key, |c| *c += size);
+        }
+
+        let (l, r) = OrderedNode::split(self.root.take(), &|k: &T| *k < key);
+        let l = OrderedNode::merge(l, Some(OrderedNode::new(key, size)));
+        self.root = OrderedNode::merge(l, r);
+        self.distinct += 1;
+        size
     }
 
     /* sizeだけ減らして新しい値を返す */
     pub fn remove(&mut self, key: T, size: U) -> Option<U> {
-        if let Some(&current_size) = self.map.get(&key) {
-            if current_size > size {
-                let new_size = current_size - size;
-                self.map.insert(key, new_size);
-                Some(new_size)
-            } else {
-                self.map.remove(&key);
-                Some(U::zero())
-            }
+        let current_size = self.get(key)?;
+        if current_size > size {
+            Some(self.modify(key, |c| *c -= size))
         } else {
-            None
+            let (l, r) = OrderedNode::split(self.root.take(), &|k: &T| *k < key);
+            let (_, r) = OrderedNode::split(r, &|k: &T| *k <= key);
+            self.root = OrderedNode::merge(l, r);
+            self.distinct -= 1;
+            Some(U::zero())
         }
     }
 
     pub fn max_key(&self) -> Option<(&T, &U)> {
-        self.map.last_key_value()
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = node.right.as_ref() {
+            node = right;
+        }
+        Some((&node.key, &node.weight))
     }
 
     pub fn min_key(&self) -> Option<(&T, &U)> {
-        self.map.first_key_value()
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = node.left.as_ref() {
+            node = left;
+        }
+        Some((&node.key, &node.weight))
     }
 
     pub fn get(&self, key: T) -> Option<U> {
-        if let Some(&v) = self.map.get(&key) {
-            Some(v)
-        } else {
-            None
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            cur = match key.cmp(&node.key) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(node.weight),
+            };
         }
+        None
     }
 
+    /// 重複を含めた要素数
     pub fn len(&self) -> usize {
-        self.map.len()
+        OrderedNode::size(&self.root).to_usize()
+    }
+
+    /// 相異なるキーの数
+    pub fn distinct_len(&self) -> usize {
+        self.distinct
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// 重複を含めて昇順に並べたときの k 番目 (0-indexed) の要素
+    pub fn nth(&self, k: U) -> Option<T> {
+        let mut k = k;
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            let left = OrderedNode::size(&node.left);
+            if k < left {
+                cur = &node.left;
+            } else if k < left + node.weight {
+                return Some(node.key);
+            } else {
+                k -= left + node.weight;
+                cur = &node.right;
+            }
+        }
+        None
+    }
+
+    /// x 未満の要素の個数 (重複を含む)
+    pub fn count_less(&self, x: T) -> U {
+        let mut res = U::zero();
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            if node.key < x {
+                res += OrderedNode::size(&node.left) + node.weight;
+                cur = &node.right;
+            } else {
+                cur = &node.left;
+            }
+        }
+        res
+    }
+
+    /// x 以上で最小のキー
+    pub fn lower_bound(&self, x: T) -> Option<T> {
+        self.range(x..).next().map(|(&k, _)| k)
+    }
+
+    /// x より大きい最小のキー
+    pub fn upper_bound(&self, x: T) -> Option<T> {
+        self.range((Bound::Excluded(x), Bound::Unbounded))
+            .next()
+            .map(|(&k, _)| k)
+    }
+
+    /// キーが range に含まれる (キー, 個数) を昇順に列挙する
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T, U> {
+        // 始点以上のキーを持つノードのうち、根からの経路上で左に降りたものを積む
+        let mut stack = Vec::new();
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            let ok = match range.start_bound() {
+                Bound::Included(s) => *s <= node.key,
+                Bound::Excluded(s) => *s < node.key,
+                Bound::Unbounded => true,
+            };
+            if ok {
+                stack.push(node.as_ref());
+                cur = &node.left;
+            } else {
+                cur = &node.right;
+            }
+        }
+        let end = match range.end_bound() {
+            Bound::Included(&e) => Bound::Included(e),
+            Bound::Excluded(&e) => Bound::Excluded(e),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Range { stack, end }
+    }
+
+    pub fn iter(&self) -> Range<'_, T, U> {
+        self.range(..)
+    }
+
+    // 既に存在する key の個数に f を適用し、根からの経路上の部分木の総和も同じだけ更新する
+    fn modify<F: Fn(&mut U)>(&mut self, key: T, f: F) -> U {
+        let mut cur = &mut self.root;
+        while let Some(node) = cur {
+            f(&mut node.size);
+            cur = match key.cmp(&node.key) {
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+                Ordering::Equal => {
+                    f(&mut node.weight);
+                    return node.weight;
+                }
+            };
+        }
+        unreachable!()
     }
+}
 
-    pub fn iter(&'_ self) -> std::collections::btree_map::Iter<'_, T, U> {
-        self.map.iter()
+/// MultiSet::range / MultiSet::iter が返すイテレータ
+pub struct Range<'a, T, U> {
+    stack: Vec<&'a OrderedNode<T, U>>,
+    end: Bound<T>,
+}
+
+impl<'a, T: Ord, U> Iterator for Range<'a, T, U> {
+    type Item = (&'a T, &'a U);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        let in_range = match &self.end {
+            Bound::Included(e) => node.key <= *e,
+            Bound::Excluded(e) => node.key < *e,
+            Bound::Unbounded => true,
+        };
+        if !in_range {
+            self.stack.clear();
+            return None;
+        }
+        let mut child = &node.right;
+        while let Some(c) = child {
+            self.stack.push(c.as_ref());
+            child = &c.left;
+        }
+        Some((&node.key, &node.weight))
     }
+}
 
-    pub fn iter_mut(&'_ mut self) -> std::collections::btree_map::IterMut<'_, T, U> {
-        self.map.iter_mut()
+/// MultiSet を消費して (キー, 個数) を昇順に返すイテレータ
+pub struct IntoIter<T, U> {
+    stack: Vec<Box<OrderedNode<T, U>>>,
+}
+
+impl<T, U> IntoIter<T, U> {
+    // link から左の子を辿ったノードを順に積む
+    fn push_left(&mut self, mut link: OrderedLink<T, U>) {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            self.stack.push(node);
+        }
     }
 }
 
-impl<T, U> IntoIterator for MultiSet<T, U> {
+impl<T, U> Iterator for IntoIter<T, U> {
     type Item = (T, U);
-    type IntoIter = std::collections::btree_map::IntoIter<T, U>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.stack.pop()?;
+        self.push_left(node.right.take());
+        let node = *node;
+        Some((node.key, node.weight))
+    }
+}
+
+impl<T, U> IntoIterator for MultiSet<T, U>
+where
+    T: Ord + Copy,
+    U: Integer,
+{
+    type Item = (T, U);
+    type IntoIter = IntoIter<T, U>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.map.into_iter()
+        let mut iter = IntoIter { stack: Vec::new() };
+        iter.push_left(self.root);
+        iter
     }
 }
 
-impl<'a, T, U> IntoIterator for &'a MultiSet<T, U> {
+impl<'a, T, U> IntoIterator for &'a MultiSet<T, U>
+where
+    T: Ord + Copy,
+    U: Integer,
+{
     type Item = (&'a T, &'a U);
-    type IntoIter = std::collections::btree_map::Iter<'a, T, U>;
+    type IntoIter = Range<'a, T, U>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.map.iter()
+        self.iter()
     }
 }
 
-impl<'a, T, U> IntoIterator for &'a mut MultiSet<T, U> {
-    type Item = (&'a T, &'a mut U);
-    type IntoIter = std::collections::btree_map::IterMut<'a, T, U>;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.map.iter_mut()
+    #[test]
+    fn test_multiset() {
+        let mut set: MultiSet<i32, usize> = MultiSet::new();
+        assert!(set.is_empty());
+        assert_eq!(set.add(5, 2), 2);
+        assert_eq!(set.add(1, 1), 1);
+        assert_eq!(set.add(8, 3), 3);
+        assert_eq!(set.add(5, 1), 3);
+        assert_eq!(set.len(), 7);
+        assert_eq!(set.distinct_len(), 3);
+
+        // [1, 5, 5, 5, 8, 8, 8]
+        let expected = [1, 5, 5, 5, 8, 8, 8];
+        for (k, &x) in expected.iter().enumerate() {
+            assert_eq!(set.nth(k), Some(x));
+        }
+        assert_eq!(set.nth(7), None);
+        assert_eq!(set.count_less(1), 0);
+        assert_eq!(set.count_less(5), 1);
+        assert_eq!(set.count_less(6), 4);
+        assert_eq!(set.count_less(100), 7);
+        assert_eq!(set.lower_bound(5), Some(5));
+        assert_eq!(set.upper_bound(5), Some(8));
+        assert_eq!(set.upper_bound(8), None);
+        assert_eq!(
+            set.range(2..=8).collect::<Vec<_>>(),
+            vec![(&5, &3), (&8, &3)]
+        );
+        assert_eq!(set.range(..8).count(), 2);
+
+        assert_eq!(set.remove(5, 2), Some(1));
+        assert_eq!(set.remove(1, 5), Some(0));
+        assert_eq!(set.remove(3, 1), None);
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.min_key(), Some((&5, &1)));
+        assert_eq!(set.max_key(), Some((&8, &3)));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![(5, 1), (8, 3)]);
+    }
+
+    #[test]
+    fn test_multiset_random() {
+        use crate::misc::rand::Pcg32;
+        let mut rng = Pcg32::with_seed(1, 2);
+        let mut set: MultiSet<u32, u64> = MultiSet::new();
+        let mut naive = vec![0u64; 50];
+        for _ in 0..2000 {
+            let key = rng.gen_range(0..50u32);
+            let size = rng.gen_range(1..4u64);
+            if rng.gen_range(0..2u32) == 0 {
+                set.add(key, size);
+                naive[key as usize] += size;
+            } else {
+                let res = set.remove(key, size);
+                if naive[key as usize] == 0 {
+                    assert_eq!(res, None);
+                } else {
+                    naive[key as usize] = naive[key as usize].saturating_sub(size);
+                    assert_eq!(res, Some(naive[key as usize]));
+                }
+            }
+            let x = rng.gen_range(0..51u32);
+            let less: u64 = naive[..x as usize].iter().sum();
+            assert_eq!(set.count_less(x), less);
+            let total: u64 = naive.iter().sum();
+            assert_eq!(set.len(), total as usize);
+            if less < total {
+                assert_eq!(set.nth(less), set.lower_bound(x));
+            }
+        }
+        let expected: Vec<_> = (0..50u32)
+            .filter(|&k| naive[k as usize] > 0)
+            .map(|k| (k, naive[k as usize]))
+            .collect();
+        assert_eq!(set.clone().into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(set.iter().count(), set.distinct_len());
     }
 }
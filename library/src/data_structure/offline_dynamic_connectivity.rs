@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use crate::data_structure::rollback_unionfind::RollbackUnionFind;
+
+/*
+ * オフライン動的連結性判定
+ * 辺の追加・削除と連結性クエリの列を受け取り、まとめて答える
+ *
+ * 各辺が存在するクエリ時刻の区間 [l, r) を、クエリ時刻上のセグメント木の O(log Q) 個のノードに載せ、
+ * 根から DFS しながら RollbackUnionFind に辺を追加・巻き戻しすることで O((N + Q) log Q log N) で処理する
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Same(bool),
+    Components(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Query {
+    Same(usize, usize),
+    Components,
+}
+
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    queries: Vec<Query>,
+    // 現在存在する辺 → 追加された時刻 (多重辺のためスタックで持つ)
+    alive: HashMap<(usize, usize), Vec<usize>>,
+    // (u, v, l, r): 辺 (u, v) がクエリ [l, r) の間存在する
+    intervals: Vec<(usize, usize, usize, usize)>,
+}
+
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            queries: Vec::new(),
+            alive: HashMap::new(),
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.n && v < self.n);
+        let key = (u.min(v), u.max(v));
+        self.alive.entry(key).or_default().push(self.queries.len());
+    }
+
+    /// 存在しない辺を削除しようとした場合は panic する
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        let key = (u.min(v), u.max(v));
+        let starts = self
+            .alive
+            .get_mut(&key)
+            .unwrap_or_else(|| panic!("edge {:?} does not exist", key));
+        let l = starts.pop().unwrap();
+        if starts.is_empty() {
+            self.alive.remove(&key);
+        }
+        if l < self.queries.len() {
+            self.intervals.push((key.0, key.1, l, self.queries.len()));
+        }
+    }
+
+    /// u と v が連結かを問うクエリを追加し、クエリ番号を返す
+    pub fn same(&mut self, u: usize, v: usize) -> usize {
+        assert!(u < self.n && v < self.n);
+        self.queries.push(Query::Same(u, v));
+        self.queries.len() - 1
+    }
+
+    /// 連結成分数を問うクエリを追加し、クエリ番号を返す
+    pub fn count_components(&mut self) -> usize {
+        self.queries.push(Query::Components);
+        self.queries.len() - 1
+    }
+
+    /// 全クエリの答えをクエリ番号順に返す
+    pub fn solve(mut self) -> Vec<Answer> {
+        let q = self.queries.len();
+        if q == 0 {
+            return vec![];
+        }
+        for (&(u, v), starts) in self.alive.iter() {
+            for &l in starts.iter() {
+                if l < q {
+                    self.intervals.push((u, v, l, q));
+                }
+            }
+        }
+
+        let mut size = 1;
+        while size < q {
+            size <<= 1;
+        }
+        let mut edges = vec![vec![]; 2 * size];
+        for &(u, v, l, r) in self.intervals.iter() {
+            let (mut l, mut r) = (l + size, r + size);
+            while l < r {
+                if l & 1 == 1 {
+                    edges[l].push((u, v));
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    edges[r].push((u, v));
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+
+        let mut uf = RollbackUnionFind::new(self.n, |_: &(), _: &()| {});
+        let mut answers = vec![Answer::Components(0); q];
+        let mut components = self.n;
+        self.dfs(1, size, &edges, &mut uf, &mut components, &mut answers);
+        answers
+    }
+
+    fn dfs<F: Fn(&(), &())>(
+        &self,
+        k: usize,
+        size: usize,
+        edges: &[Vec<(usize, usize)>],
+        uf: &mut RollbackUnionFind<(), F>,
+        components: &mut usize,
+        answers: &mut [Answer],
+    ) {
+        if k >= size && k - size >= self.queries.len() {
+            return;
+        }
+        let snapshot = uf.snapshot();
+        let before = *components;
+        for &(u, v) in edges[k].iter() {
+            if uf.merge(u, v) {
+                *components -= 1;
+            }
+        }
+        if k >= size {
+            answers[k - size] = match self.queries[k - size] {
+                Query::Same(u, v) => Answer::Same(uf.same(u, v)),
+                Query::Components => Answer::Components(*components),
+            };
+        } else {
+            self.dfs(2 * k, size, edges, uf, components, answers);
+            self.dfs(2 * k + 1, size, edges, uf, components, answers);
+        }
+        uf.rollback(snapshot);
+        *components = before;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_dynamic_connectivity() {
+        let mut dc = OfflineDynamicConnectivity::new(4);
+        dc.count_components();
+        dc.add_edge(0, 1);
+        dc.add_edge(1, 2);
+        dc.same(0, 2);
+        dc.count_components();
+        dc.add_edge(2, 1);
+        dc.remove_edge(1, 2);
+        dc.same(0, 2);
+        dc.remove_edge(2, 1);
+        dc.same(0, 2);
+        dc.add_edge(3, 2);
+        dc.count_components();
+        dc.remove_edge(0, 1);
+        dc.same(1, 3);
+        dc.add_edge(0, 3);
+        dc.same(0, 3);
+        assert_eq!(
+            dc.solve(),
+            vec![
+                Answer::Components(4),
+                Answer::Same(true),
+                Answer::Components(2),
+                Answer::Same(true),
+                Answer::Same(false),
+                Answer::Components(2),
+                Answer::Same(false),
+                Answer::Same(true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+/// 部分永続 UnionFind
+/// merge を呼ぶたびに時刻が 1 進み、過去の任意の時刻の状態に対して問い合わせができる
+/// 時刻 t の状態とは、t 回目までの merge を行った状態 (時刻 0 は初期状態)
+///
+/// 経路圧縮を行わず union by size のみで木の高さを O(log n) に抑え、
+/// 各頂点が親を持った時刻を記録しておく
+pub struct PartiallyPersistentUnionFind {
+    now: usize,
+    parent: Vec<usize>,
+    // 親を持った時刻。根なら usize::MAX
+    time: Vec<usize>,
+    // 根である間の (時刻, その時刻以降のサイズ) の履歴
+    sizes: Vec<Vec<(usize, usize)>>,
+}
+
+impl PartiallyPersistentUnionFind {
+    pub fn new(size: usize) -> Self {
+        Self {
+            now: 0,
+            parent: (0..size).collect(),
+            time: vec![usize::MAX; size],
+            sizes: vec![vec![(0, 1)]; size],
+        }
+    }
+
+    /// 現在の時刻 (これまでの merge の呼び出し回数)
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    /// 時刻を 1 進めて u と v を併合する。すでに同じ集合なら false を返す
+    pub fn merge(&mut self, u: usize, v: usize) -> bool {
+        self.now += 1;
+        let mut u = self.leader_at(u, self.now);
+        let mut v = self.leader_at(v, self.now);
+        if u == v {
+            return false;
+        }
+        let (su, sv) = (self.size_at(u, self.now), self.size_at(v, self.now));
+        if su < sv {
+            std::mem::swap(&mut u, &mut v);
+        }
+        self.parent[v] = u;
+        self.time[v] = self.now;
+        self.sizes[u].push((self.now, su + sv));
+        true
+    }
+
+    /// 時刻 t における u の代表元
+    pub fn leader_at(&self, u: usize, t: usize) -> usize {
+        let mut cur = u;
+        while self.time[cur] <= t {
+            cur = self.parent[cur];
+        }
+        cur
+    }
+
+    /// 時刻 t において u と v が同じ集合に属するか
+    pub fn same_at(&self, u: usize, v: usize, t: usize) -> bool {
+        self.leader_at(u, t) == self.leader_at(v, t)
+    }
+
+    /// 時刻 t における u を含む集合の大きさ
+    pub fn size_at(&self, u: usize, t: usize) -> usize {
+        let root = self.leader_at(u, t);
+        let hist = &self.sizes[root];
+        let idx = hist.partition_point(|&(time, _)| time <= t);
+        hist[idx - 1].1
+    }
+
+    /// u と v が初めて同じ集合に属した時刻。現在も異なる集合なら None
+    pub fn connected_time(&self, u: usize, v: usize) -> Option<usize> {
+        let (mut u, mut v) = (u, v);
+        let mut res = 0;
+        // 親を持った時刻が小さい方から辿ると、使った辺の時刻は単調増加になる
+        while u != v {
+            if self.time[u] > self.time[v] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            if self.time[u] == usize::MAX {
+                return None;
+            }
+            res = self.time[u];
+            u = self.parent[u];
+        }
+        Some(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partially_persistent_unionfind() {
+        let mut uf = PartiallyPersistentUnionFind::new(6);
+        assert!(uf.merge(0, 1)); // t = 1
+        assert!(uf.merge(2, 3)); // t = 2
+        assert!(!uf.merge(1, 0)); // t = 3
+        assert!(uf.merge(3, 4)); // t = 4
+        assert!(uf.merge(1, 4)); // t = 5
+        assert_eq!(uf.now(), 5);
+
+        assert!(!uf.same_at(0, 1, 0));
+        assert!(uf.same_at(0, 1, 1));
+        assert!(!uf.same_at(0, 4, 4));
+        assert!(uf.same_at(0, 4, 5));
+        assert_eq!(uf.size_at(2, 1), 1);
+        assert_eq!(uf.size_at(2, 2), 2);
+        assert_eq!(uf.size_at(4, 4), 3);
+        assert_eq!(uf.size_at(0, 5), 5);
+
+        assert_eq!(uf.connected_time(0, 0), Some(0));
+        assert_eq!(uf.connected_time(0, 1), Some(1));
+        assert_eq!(uf.connected_time(2, 4), Some(4));
+        assert_eq!(uf.connected_time(0, 3), Some(5));
+        assert_eq!(uf.connected_time(0, 5), None);
+    }
+}
@@ -1,7 +1,9 @@
-use std::ops::Neg;
+use std::ops::{BitXor, Neg};
 
-use crate::utils::integer::Integer;
+use crate::{misc::modint::Modint, utils::integer::Integer};
 
+/// ポテンシャルが属する群の演算
+/// 可換である必要はない。merge(a, b) は「a を行ったあとに b を行う」合成 a・b を表す
 pub trait PotentialMergeOp<T> {
     fn identity() -> T;
     fn merge(a: T, b: T) -> T;
@@ -29,19 +31,92 @@ impl Default for DefaultPotentialMergeOp {
     }
 }
 
+/// XOR を演算とする群 (bit 列・F2 ベクトル)
+#[derive(Default)]
+pub struct XorPotentialMergeOp;
+impl<T: Copy + Default + BitXor<Output = T>> PotentialMergeOp<T> for XorPotentialMergeOp {
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn merge(a: T, b: T) -> T {
+        a ^ b
+    }
+
+    fn invert(a: T) -> T {
+        a
+    }
+}
+
+/// 置換群。p は i を p[i] に移す置換を表し、merge(a, b) は a で移したあとに b で移す
+#[derive(Default)]
+pub struct PermutationPotentialMergeOp;
+impl<const N: usize> PotentialMergeOp<[usize; N]> for PermutationPotentialMergeOp {
+    fn identity() -> [usize; N] {
+        let mut res = [0; N];
+        for (i, r) in res.iter_mut().enumerate() {
+            *r = i;
+        }
+        res
+    }
+
+    fn merge(a: [usize; N], b: [usize; N]) -> [usize; N] {
+        let mut res = [0; N];
+        for i in 0..N {
+            res[i] = b[a[i]];
+        }
+        res
+    }
+
+    fn invert(a: [usize; N]) -> [usize; N] {
+        let mut res = [0; N];
+        for i in 0..N {
+            res[a[i]] = i;
+        }
+        res
+    }
+}
+
+/// アフィン変換 x -> ax + b (a != 0) のなす群。(a, b) で表す
+/// merge(f, g) は f を適用したあとに g を適用する変換
+#[derive(Default)]
+pub struct AffinePotentialMergeOp;
+impl<const MOD: u64> PotentialMergeOp<(Modint<MOD>, Modint<MOD>)> for AffinePotentialMergeOp {
+    fn identity() -> (Modint<MOD>, Modint<MOD>) {
+        (Modint::new(1), Modint::new(0))
+    }
+
+    fn merge(
+        f: (Modint<MOD>, Modint<MOD>),
+        g: (Modint<MOD>, Modint<MOD>),
+    ) -> (Modint<MOD>, Modint<MOD>) {
+        (g.0 * f.0, g.0 * f.1 + g.1)
+    }
+
+    fn invert(f: (Modint<MOD>, Modint<MOD>)) -> (Modint<MOD>, Modint<MOD>) {
+        let a_inv = Modint::new(1) / f.0;
+        (a_inv, Modint::new(0) - a_inv * f.1)
+    }
+}
+
+/// 重み付き UnionFind
+/// 各頂点に群の元であるポテンシャル p(u) を割り当て、
+/// 制約 p(v) = p(u)・w を追加する merge(u, v, w) と、
+/// diff(u, v) = p(u)^{-1}・p(v) (u から v へ移る元) の取得を行う
 pub struct PotentialityUnionfind<T, F: PotentialMergeOp<T> + Default> {
     vertex: Vec<usize>,
+    // 親 par に対して p(par)^{-1}・p(u) を持つ
     diff_weights: Vec<T>,
     #[allow(dead_code)]
     merge_op: F,
 }
 
-impl<T: Integer, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
+impl<T: Clone + PartialEq, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
     pub fn new(size: usize, op: Option<F>) -> Self {
         let op = op.unwrap_or_default();
         Self {
             vertex: vec![!1; size],
-            diff_weights: vec![T::zero(); size],
+            diff_weights: vec![F::identity(); size],
             merge_op: op,
         }
     }
@@ -52,7 +127,9 @@ impl<T: Integer, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
             u
         } else {
             let parent = self.leader(elm);
-            self.diff_weights[u] = F::merge(self.diff_weights[u], self.diff_weights[elm]);
+            // p(root)^{-1}・p(u) = (p(root)^{-1}・p(elm))・(p(elm)^{-1}・p(u))
+            self.diff_weights[u] =
+                F::merge(self.diff_weights[elm].clone(), self.diff_weights[u].clone());
 
             self.vertex[u] = parent;
             self.vertex[u]
@@ -63,7 +140,7 @@ impl<T: Integer, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
         self.leader(u) == self.leader(v)
     }
 
-    // マージして重みを返す
+    // p(v) = p(u)・w となるようにマージして重みを返す
     // すでに同じ場合は「既存の情報と矛盾しないか？」を判定して、
     // 矛盾する場合はErrを返す
     pub fn merge(&mut self, u: usize, v: usize, w: T) -> Result<T, T> {
@@ -73,13 +150,14 @@ impl<T: Integer, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
 
         // size(u) >= size(v) となるようにswap
         if self.size(u) < self.size(v) {
-            self.merge(v, u, F::invert(w))?;
-            return Ok(F::invert(w));
+            self.merge(v, u, F::invert(w.clone()))?;
+            return Ok(w);
         }
 
+        // p(v_leader) = p(u_leader)・weight(u)・w・weight(v)^{-1}
         let w_sub = F::merge(
-            w,
-            F::merge(self.diff_weights[u], F::invert(self.diff_weights[v])),
+            F::merge(self.weight(u), w.clone()),
+            F::invert(self.weight(v)),
         );
 
         // vの親をuに変更
@@ -98,18 +176,20 @@ impl<T: Integer, F: PotentialMergeOp<T> + Default> PotentialityUnionfind<T, F> {
         !self.vertex[idx]
     }
 
+    // p(u)^{-1}・p(v) を返す
     // u, vが違う集合の場合はErrとして返す
     pub fn diff(&mut self, u: usize, v: usize) -> Result<T, ()> {
         if self.same(u, v) {
-            Ok(F::merge(self.weight(v), F::invert(self.weight(u))))
+            Ok(F::merge(F::invert(self.weight(u)), self.weight(v)))
         } else {
             Err(())
         }
     }
 
+    // p(root)^{-1}・p(u)
     fn weight(&mut self, u: usize) -> T {
         self.leader(u);
-        self.diff_weights[u]
+        self.diff_weights[u].clone()
     }
 }
 
@@ -131,4 +211,42 @@ mod tests {
         assert_eq!(uf.diff(0, 4), Ok(7));
         assert_eq!(uf.diff(0, 5), Err(()));
     }
+
+    #[test]
+    fn test_potentiality_unionfind_permutation() {
+        type Op = PermutationPotentialMergeOp;
+        let a = [1, 2, 0];
+        let b = [1, 0, 2];
+        let mut uf: PotentialityUnionfind<[usize; 3], Op> = PotentialityUnionfind::new(5, None);
+        assert_eq!(uf.merge(0, 1, a), Ok(a));
+        assert_eq!(uf.merge(2, 1, b), Ok(b));
+        assert_eq!(uf.merge(3, 4, a), Ok(a));
+        assert_eq!(uf.merge(4, 2, b), Ok(b));
+        // p(1) = p(0)・a = p(2)・b より p(2) = p(0)・a・b^{-1}
+        let ab_inv = Op::merge(a, Op::invert(b));
+        assert_eq!(uf.diff(0, 2), Ok(ab_inv));
+        assert_ne!(ab_inv, Op::merge(Op::invert(b), a));
+        assert_eq!(uf.diff(2, 0), Ok(Op::invert(ab_inv)));
+        // p(2) = p(3)・a・b
+        assert_eq!(uf.diff(3, 2), Ok(Op::merge(a, b)));
+        assert_eq!(
+            uf.diff(3, 0),
+            Ok(Op::merge(Op::merge(a, b), Op::invert(ab_inv)))
+        );
+        assert!(uf.merge(0, 3, a).is_err());
+    }
+
+    #[test]
+    fn test_potentiality_unionfind_affine() {
+        type Mint = Modint<998_244_353>;
+        type Op = AffinePotentialMergeOp;
+        let f = (Mint::new(2), Mint::new(3));
+        let g = (Mint::new(5), Mint::new(7));
+        let mut uf: PotentialityUnionfind<(Mint, Mint), Op> = PotentialityUnionfind::new(3, None);
+        assert_eq!(uf.merge(1, 0, f), Ok(f));
+        assert_eq!(uf.merge(1, 2, g), Ok(g));
+        // p(2) = p(0)・f^{-1}・g
+        assert_eq!(uf.diff(0, 2), Ok(Op::merge(Op::invert(f), g)));
+        assert_eq!(Op::merge(f, Op::invert(f)), Op::identity());
+    }
 }
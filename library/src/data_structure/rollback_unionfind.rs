@@ -0,0 +1,175 @@
+enum History<T> {
+    // child を parent の下につないだ。両者の元の状態を持つ
+    Merge {
+        child: usize,
+        child_vertex: usize,
+        parent: usize,
+        parent_vertex: usize,
+        parent_data: Option<T>,
+    },
+    // root の data を書き換えた。元の値を持つ
+    Data {
+        root: usize,
+        data: Option<T>,
+    },
+}
+
+/// 操作を取り消せる UnionFind
+/// 経路圧縮を行わず union by size のみで O(log n) を保証する
+/// data / merge_op の扱いは UnionFind と同じ
+pub struct RollbackUnionFind<T, F>
+where
+    F: Fn(&T, &T) -> T,
+{
+    vertex: Vec<usize>,
+    data: Vec<Option<T>>,
+    merge_op: F,
+    history: Vec<History<T>>,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> RollbackUnionFind<T, F> {
+    pub fn new(size: usize, merge_op: F) -> Self {
+        RollbackUnionFind {
+            vertex: vec![!1; size],
+            data: vec![None; size],
+            merge_op,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn leader(&self, u: usize) -> usize {
+        let mut cur = u;
+        while self.vertex[cur] < self.vertex.len() {
+            cur = self.vertex[cur];
+        }
+        cur
+    }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.leader(u) == self.leader(v)
+    }
+
+    pub fn size(&self, u: usize) -> usize {
+        !self.vertex[self.leader(u)]
+    }
+
+    // マージに成功した場合のみ履歴に積まれる
+    pub fn merge(&mut self, u: usize, v: usize) -> bool {
+        let (mut parent, mut child) = (self.leader(u), self.leader(v));
+        if parent == child {
+            return false;
+        }
+        if self.size(parent) < self.size(child) {
+            std::mem::swap(&mut parent, &mut child);
+        }
+
+        self.history.push(History::Merge {
+            child,
+            child_vertex: self.vertex[child],
+            parent,
+            parent_vertex: self.vertex[parent],
+            parent_data: self.data[parent].clone(),
+        });
+
+        let merged_size = !(self.size(parent) + self.size(child));
+        self.vertex[parent] = merged_size;
+        self.vertex[child] = parent;
+        self.data[parent] = match (&self.data[parent], &self.data[child]) {
+            (Some(dp), Some(dc)) => Some((self.merge_op)(dp, dc)),
+            (None, None) => None,
+            _ => {
+                unreachable!();
+            }
+        };
+
+        true
+    }
+
+    pub fn insert_data(&mut self, u: usize, value: T) {
+        let root = self.leader(u);
+        let old = self.data[root].replace(value);
+        self.history.push(History::Data { root, data: old });
+    }
+
+    pub fn get_data(&self, u: usize) -> Option<&T> {
+        self.data[self.leader(u)].as_ref()
+    }
+
+    /// 現在の状態を表す値を返す。rollback に渡すとこの時点まで戻せる
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// snapshot を取った時点の状態まで戻す
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len());
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+
+    /// 直前の (成功した) merge もしくは insert_data を取り消す
+    /// 取り消す操作がなければ false を返す
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(History::Merge {
+                child,
+                child_vertex,
+                parent,
+                parent_vertex,
+                parent_data,
+            }) => {
+                self.vertex[child] = child_vertex;
+                self.vertex[parent] = parent_vertex;
+                self.data[parent] = parent_data;
+                true
+            }
+            Some(History::Data { root, data }) => {
+                self.data[root] = data;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_unionfind() {
+        let mut uf = RollbackUnionFind::new(5, |a: &u64, b: &u64| a + b);
+        for i in 0..5 {
+            uf.insert_data(i, i as u64 * 10);
+        }
+        let base = uf.snapshot();
+        assert!(uf.merge(0, 1));
+        assert!(uf.merge(2, 3));
+        let mid = uf.snapshot();
+        assert!(uf.merge(1, 3));
+        assert!(!uf.merge(0, 2));
+        assert_eq!(uf.size(2), 4);
+        assert_eq!(uf.get_data(0), Some(&60));
+
+        assert!(uf.undo());
+        assert_eq!(uf.snapshot(), mid);
+        assert!(!uf.same(0, 2));
+        assert_eq!(uf.size(0), 2);
+        assert_eq!(uf.size(3), 2);
+        assert_eq!(uf.get_data(1), Some(&10));
+        assert_eq!(uf.get_data(2), Some(&50));
+
+        uf.insert_data(4, 100);
+        assert!(uf.merge(4, 0));
+        assert_eq!(uf.get_data(1), Some(&110));
+        uf.rollback(base);
+        for i in 0..5 {
+            assert_eq!(uf.size(i), 1);
+            assert_eq!(uf.get_data(i), Some(&(i as u64 * 10)));
+        }
+        uf.rollback(0);
+        assert_eq!(uf.get_data(0), None);
+        assert!(!uf.undo());
+    }
+}
@@ -0,0 +1,341 @@
+use std::ops::RangeBounds;
+
+use crate::{
+    data_structure::segtree::monoids::{MapMonoid, Monoid},
+    utils::versatility_functions::unpack_range,
+};
+
+/*
+ * 動的セグメント木
+ * 添字の範囲 [lo, hi) が巨大な場合でも、触れたノードだけを生成するので
+ * クエリ数 Q に対して O(Q log(hi - lo)) のメモリで済む
+*/
+
+#[derive(Debug, Clone)]
+struct Node<S> {
+    val: S,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+pub struct DynamicSegmentTree<T: Monoid> {
+    lo: i64,
+    hi: i64,
+    nodes: Vec<Node<T::S>>,
+}
+
+impl<T: Monoid> DynamicSegmentTree<T> {
+    /// 添字の範囲を [lo, hi) とする
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo < hi);
+        Self {
+            lo,
+            hi,
+            nodes: vec![Node {
+                val: T::id(),
+                left: None,
+                right: None,
+            }],
+        }
+    }
+
+    pub fn set(&mut self, index: i64, value: T::S) {
+        assert!(self.lo <= index && index < self.hi);
+        self.set_rec(0, self.lo, self.hi, index, value);
+    }
+
+    pub fn get_point(&self, index: i64) -> T::S {
+        assert!(self.lo <= index && index < self.hi);
+        let (mut cur, mut l, mut r) = (0, self.lo, self.hi);
+        while r - l > 1 {
+            let mid = l + (r - l) / 2;
+            let nxt = if index < mid {
+                r = mid;
+                self.nodes[cur].left
+            } else {
+                l = mid;
+                self.nodes[cur].right
+            };
+            match nxt {
+                Some(nxt) => cur = nxt,
+                None => return T::id(),
+            }
+        }
+        self.nodes[cur].val
+    }
+
+    pub fn get<R: RangeBounds<i64>>(&self, range: R) -> T::S {
+        let (l, r) = unpack_range(range, self.lo, self.hi);
+        assert!(self.lo <= l && l <= r && r <= self.hi);
+        self.get_rec(Some(0), self.lo, self.hi, l, r)
+    }
+
+    /// ノード数 (メモリ使用量の目安)
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn new_node(&mut self) -> usize {
+        self.nodes.push(Node {
+            val: T::id(),
+            left: None,
+            right: None,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn set_rec(&mut self, cur: usize, seg_l: i64, seg_r: i64, index: i64, value: T::S) {
+        if seg_r - seg_l == 1 {
+            self.nodes[cur].val = value;
+            return;
+        }
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        if index < mid {
+            let child = match self.nodes[cur].left {
+                Some(c) => c,
+                None => {
+                    let c = self.new_node();
+                    self.nodes[cur].left = Some(c);
+                    c
+                }
+            };
+            self.set_rec(child, seg_l, mid, index, value);
+        } else {
+            let child = match self.nodes[cur].right {
+                Some(c) => c,
+                None => {
+                    let c = self.new_node();
+                    self.nodes[cur].right = Some(c);
+                    c
+                }
+            };
+            self.set_rec(child, mid, seg_r, index, value);
+        }
+        let left = self.nodes[cur].left.map_or(T::id(), |c| self.nodes[c].val);
+        let right = self.nodes[cur].right.map_or(T::id(), |c| self.nodes[c].val);
+        self.nodes[cur].val = T::op(left, right);
+    }
+
+    fn get_rec(&self, cur: Option<usize>, seg_l: i64, seg_r: i64, q_l: i64, q_r: i64) -> T::S {
+        let cur = match cur {
+            Some(c) => c,
+            None => return T::id(),
+        };
+        if seg_r <= q_l || q_r <= seg_l {
+            return T::id();
+        }
+        if q_l <= seg_l && seg_r <= q_r {
+            return self.nodes[cur].val;
+        }
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        let left = self.get_rec(self.nodes[cur].left, seg_l, mid, q_l, q_r);
+        let right = self.get_rec(self.nodes[cur].right, mid, seg_r, q_l, q_r);
+        T::op(left, right)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LazyNode<S, F> {
+    val: S,
+    lazy: F,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// 遅延評価つきの動的セグメント木
+/// 未生成の区間 [l, r) の値は init(l, r) で与える (既定では単位元)
+pub struct DynamicLazySegmentTree<T: MapMonoid> {
+    lo: i64,
+    hi: i64,
+    nodes: Vec<LazyNode<<T::M as Monoid>::S, T::F>>,
+    init: fn(i64, i64) -> <T::M as Monoid>::S,
+}
+
+impl<T: MapMonoid> DynamicLazySegmentTree<T> {
+    pub fn new(lo: i64, hi: i64) -> Self {
+        Self::with_init(lo, hi, |_, _| T::id())
+    }
+
+    /// 区間長を持つモノイド (RangeSumWithLenMonoid など) では
+    /// init = |l, r| (0, r - l) のように初期値を与える
+    pub fn with_init(lo: i64, hi: i64, init: fn(i64, i64) -> <T::M as Monoid>::S) -> Self {
+        assert!(lo < hi);
+        Self {
+            lo,
+            hi,
+            nodes: vec![LazyNode {
+                val: init(lo, hi),
+                lazy: T::identity_map(),
+                left: None,
+                right: None,
+            }],
+            init,
+        }
+    }
+
+    pub fn set(&mut self, index: i64, value: <T::M as Monoid>::S) {
+        assert!(self.lo <= index && index < self.hi);
+        self.set_rec(0, self.lo, self.hi, index, value);
+    }
+
+    pub fn update<R: RangeBounds<i64>>(&mut self, range: R, f: &T::F) {
+        let (l, r) = unpack_range(range, self.lo, self.hi);
+        assert!(self.lo <= l && l <= r && r <= self.hi);
+        self.update_rec(0, self.lo, self.hi, l, r, f);
+    }
+
+    pub fn get<R: RangeBounds<i64>>(&mut self, range: R) -> <T::M as Monoid>::S {
+        let (l, r) = unpack_range(range, self.lo, self.hi);
+        assert!(self.lo <= l && l <= r && r <= self.hi);
+        self.get_rec(0, self.lo, self.hi, l, r)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn new_node(&mut self, l: i64, r: i64) -> usize {
+        self.nodes.push(LazyNode {
+            val: (self.init)(l, r),
+            lazy: T::identity_map(),
+            left: None,
+            right: None,
+        });
+        self.nodes.len() - 1
+    }
+
+    // 子を (必要なら生成して) 作用を伝播し、子の index を返す
+    fn push(&mut self, cur: usize, seg_l: i64, seg_r: i64) -> (usize, usize) {
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        let left = match self.nodes[cur].left {
+            Some(c) => c,
+            None => {
+                let c = self.new_node(seg_l, mid);
+                self.nodes[cur].left = Some(c);
+                c
+            }
+        };
+        let right = match self.nodes[cur].right {
+            Some(c) => c,
+            None => {
+                let c = self.new_node(mid, seg_r);
+                self.nodes[cur].right = Some(c);
+                c
+            }
+        };
+        if self.nodes[cur].lazy != T::identity_map() {
+            let f = std::mem::replace(&mut self.nodes[cur].lazy, T::identity_map());
+            self.apply_node(left, &f);
+            self.apply_node(right, &f);
+        }
+        (left, right)
+    }
+
+    fn apply_node(&mut self, k: usize, f: &T::F) {
+        let node = &mut self.nodes[k];
+        node.val = T::mapping(f, &node.val);
+        node.lazy = T::composition(f, &node.lazy);
+    }
+
+    fn pull(&mut self, cur: usize, left: usize, right: usize) {
+        self.nodes[cur].val = T::op(&self.nodes[left].val, &self.nodes[right].val);
+    }
+
+    fn set_rec(
+        &mut self,
+        cur: usize,
+        seg_l: i64,
+        seg_r: i64,
+        index: i64,
+        value: <T::M as Monoid>::S,
+    ) {
+        if seg_r - seg_l == 1 {
+            self.nodes[cur].val = value;
+            return;
+        }
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        let (left, right) = self.push(cur, seg_l, seg_r);
+        if index < mid {
+            self.set_rec(left, seg_l, mid, index, value);
+        } else {
+            self.set_rec(right, mid, seg_r, index, value);
+        }
+        self.pull(cur, left, right);
+    }
+
+    fn update_rec(&mut self, cur: usize, seg_l: i64, seg_r: i64, q_l: i64, q_r: i64, f: &T::F) {
+        if seg_r <= q_l || q_r <= seg_l {
+            return;
+        }
+        if q_l <= seg_l && seg_r <= q_r {
+            self.apply_node(cur, f);
+            return;
+        }
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        let (left, right) = self.push(cur, seg_l, seg_r);
+        self.update_rec(left, seg_l, mid, q_l, q_r, f);
+        self.update_rec(right, mid, seg_r, q_l, q_r, f);
+        self.pull(cur, left, right);
+    }
+
+    fn get_rec(
+        &mut self,
+        cur: usize,
+        seg_l: i64,
+        seg_r: i64,
+        q_l: i64,
+        q_r: i64,
+    ) -> <T::M as Monoid>::S {
+        if seg_r <= q_l || q_r <= seg_l {
+            return T::id();
+        }
+        if q_l <= seg_l && seg_r <= q_r {
+            return self.nodes[cur].val;
+        }
+        let mid = seg_l + (seg_r - seg_l) / 2;
+        let (left, right) = self.push(cur, seg_l, seg_r);
+        T::op(
+            &self.get_rec(left, seg_l, mid, q_l, q_r),
+            &self.get_rec(right, mid, seg_r, q_l, q_r),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::segtree::monoids::{RangeAddRangeSum, RangeSumMonoid};
+
+    #[test]
+    fn test_dynamic_segment_tree() {
+        let mut seg = DynamicSegmentTree::<RangeSumMonoid<i64>>::new(
+            -1_000_000_000_000_000_000,
+            1_000_000_000_000_000_000,
+        );
+        seg.set(-5, 3);
+        seg.set(1_000_000_000_000, 10);
+        seg.set(999_999_999_999_999_999, 7);
+        assert_eq!(seg.get(..), 20);
+        assert_eq!(seg.get(-5..1_000_000_000_000), 3);
+        assert_eq!(seg.get(-4..=1_000_000_000_000), 10);
+        assert_eq!(seg.get_point(999_999_999_999_999_999), 7);
+        assert_eq!(seg.get_point(0), 0);
+        seg.set(-5, 1);
+        assert_eq!(seg.get(..0), 1);
+    }
+
+    #[test]
+    fn test_dynamic_lazy_segment_tree() {
+        let mut seg =
+            DynamicLazySegmentTree::<RangeAddRangeSum<i64>>::with_init(0, 1_000_000_000, |l, r| {
+                (0, r - l)
+            });
+        seg.update(10..20, &3);
+        seg.update(15..1_000_000_000, &1);
+        assert_eq!(seg.get(..).0, 30 + 1_000_000_000 - 15);
+        assert_eq!(seg.get(12..17).0, 15 + 2);
+        seg.set(16, (100, 1));
+        assert_eq!(seg.get(16..=16).0, 100);
+        assert_eq!(seg.get(10..20).0, 30 + 5 - 4 + 100);
+    }
+}
@@ -11,8 +11,11 @@ use crate::{
  *  → 可換なら演算足せばいいし、そうじゃないならgの作用の有無で場合分け
 */
 
+/// 非再帰の遅延評価セグメント木
+/// lazy[k] は「data[k] には適用済みで、子にはまだ伝播していない作用」を表す
 pub struct LazySegmentTree<T: MapMonoid> {
     n: usize,
+    log: u32,
     len: usize,
     data: Vec<<T::M as Monoid>::S>,
     lazy: Vec<T::F>,
@@ -20,89 +23,290 @@ pub struct LazySegmentTree<T: MapMonoid> {
 
 impl<T: MapMonoid> LazySegmentTree<T> {
     pub fn new(len: usize) -> Self {
+        Self::from_vec(&vec![T::id(); len])
+    }
+
+    pub fn from_vec(vec: &[<T::M as Monoid>::S]) -> Self {
+        let len = vec.len();
         let mut n = 1;
-        while n <= len {
+        while n < len {
             n <<= 1;
         }
-        Self {
+        let log = n.trailing_zeros();
+        let mut data = vec![T::id(); 2 * n];
+        data[n..][..len].copy_from_slice(vec);
+        let mut seg = Self {
             n,
+            log,
             len,
-            data: vec![T::id(); 2 * n],
-            lazy: vec![T::identity_map(); 2 * n],
+            data,
+            lazy: vec![T::identity_map(); n],
+        };
+        for i in (1..n).rev() {
+            seg.pull(i);
         }
+        seg
     }
 
     pub fn set(&mut self, index: usize, value: <T::M as Monoid>::S) {
-        self.data[self.n + index - 1] = value;
+        assert!(index < self.len);
+        let idx = index + self.n;
+        for i in (1..=self.log).rev() {
+            self.push(idx >> i);
+        }
+        self.data[idx] = value;
+        for i in 1..=self.log {
+            self.pull(idx >> i);
+        }
+    }
+
+    pub fn get_point(&mut self, index: usize) -> <T::M as Monoid>::S {
+        assert!(index < self.len);
+        let idx = index + self.n;
+        for i in (1..=self.log).rev() {
+            self.push(idx >> i);
+        }
+        self.data[idx]
+    }
+
+    pub fn get<R: RangeBounds<usize>>(&mut self, range: R) -> <T::M as Monoid>::S {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        if l == r {
+            return T::id();
+        }
+
+        let (mut l, mut r) = (l + self.n, r + self.n);
+        self.push_boundary(l, r);
+
+        let mut left = T::id();
+        let mut right = T::id();
+        while l < r {
+            if l & 1 == 1 {
+                left = T::op(&left, &self.data[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = T::op(&self.data[r], &right);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        T::op(&left, &right)
     }
 
-    fn eval(&mut self, idx: usize) {
-        /* 配列のk番目の要素を更新する */
+    pub fn all_prod(&self) -> <T::M as Monoid>::S {
+        self.data[1]
+    }
 
-        if self.lazy[idx] == T::identity_map() {
-            // 更新なし
+    pub fn update<R: RangeBounds<usize>>(&mut self, range: R, f: &T::F) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        if l == r {
             return;
         }
 
-        if idx < self.n - 1 {
-            // 子要素に伝播
-            self.lazy[idx * 2 + 1] = T::composition(&self.lazy[idx], &self.lazy[idx * 2 + 1]);
-            self.lazy[idx * 2 + 2] = T::composition(&self.lazy[idx], &self.lazy[idx * 2 + 2]);
+        let (l, r) = (l + self.n, r + self.n);
+        self.push_boundary(l, r);
+
+        {
+            let (mut l, mut r) = (l, r);
+            while l < r {
+                if l & 1 == 1 {
+                    self.apply_node(l, f);
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    self.apply_node(r, f);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
         }
 
-        self.data[idx] = T::mapping(&self.lazy[idx], &self.data[idx]);
-        self.lazy[idx] = T::identity_map();
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.pull(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.pull((r - 1) >> i);
+            }
+        }
     }
 
-    fn get_range_rec(
-        &mut self,
-        l: usize,
-        r: usize,
-        k: usize,
-        seg_l: usize,
-        seg_r: usize,
-    ) -> <T::M as Monoid>::S {
-        self.eval(k);
-        if seg_r <= l || r <= seg_l {
-            return T::id();
+    /// pred(op(a[l..r])) が true となる最大の r を返す
+    /// pred は単調であり、pred(id) = true であることを要求する
+    pub fn max_right<F>(&mut self, l: usize, pred: F) -> usize
+    where
+        F: Fn(&<T::M as Monoid>::S) -> bool,
+    {
+        assert!(l <= self.len);
+        assert!(pred(&T::id()));
+        if l == self.len {
+            return self.len;
+        }
+        let mut cur = l + self.n;
+        for i in (1..=self.log).rev() {
+            self.push(cur >> i);
+        }
+        let mut acc = T::id();
+        loop {
+            while cur & 1 == 0 {
+                cur >>= 1;
+            }
+            if !pred(&T::op(&acc, &self.data[cur])) {
+                while cur < self.n {
+                    self.push(cur);
+                    cur <<= 1;
+                    let nxt = T::op(&acc, &self.data[cur]);
+                    if pred(&nxt) {
+                        acc = nxt;
+                        cur += 1;
+                    }
+                }
+                return cur - self.n;
+            }
+            acc = T::op(&acc, &self.data[cur]);
+            cur += 1;
+            if cur & cur.wrapping_neg() == cur {
+                break;
+            }
         }
-        if l <= seg_l && seg_r <= r {
-            return self.data[k];
+        self.len
+    }
+
+    /// pred(op(a[l..r])) が true となる最小の l を返す
+    /// pred は単調であり、pred(id) = true であることを要求する
+    pub fn min_left<F>(&mut self, r: usize, pred: F) -> usize
+    where
+        F: Fn(&<T::M as Monoid>::S) -> bool,
+    {
+        assert!(r <= self.len);
+        assert!(pred(&T::id()));
+        if r == 0 {
+            return 0;
+        }
+        let mut cur = r + self.n;
+        for i in (1..=self.log).rev() {
+            self.push((cur - 1) >> i);
         }
+        let mut acc = T::id();
+        loop {
+            cur -= 1;
+            while cur > 1 && cur % 2 == 1 {
+                cur >>= 1;
+            }
+            if !pred(&T::op(&self.data[cur], &acc)) {
+                while cur < self.n {
+                    self.push(cur);
+                    cur = (cur << 1) + 1;
+                    let nxt = T::op(&self.data[cur], &acc);
+                    if pred(&nxt) {
+                        acc = nxt;
+                        cur -= 1;
+                    }
+                }
+                return cur + 1 - self.n;
+            }
+            acc = T::op(&self.data[cur], &acc);
+            if cur & cur.wrapping_neg() == cur {
+                break;
+            }
+        }
+        0
+    }
 
-        T::op(
-            &self.get_range_rec(l, r, 2 * k + 1, seg_l, (seg_l + seg_r) / 2),
-            &self.get_range_rec(l, r, 2 * k + 2, (seg_l + seg_r) / 2, seg_r),
-        )
+    // [l, r) (葉のindex) の境界にある祖先の作用を上から伝播する
+    fn push_boundary(&mut self, l: usize, r: usize) {
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
     }
 
-    pub fn get<R: RangeBounds<usize>>(&mut self, range: R) -> <T::M as Monoid>::S {
-        let (l, r) = unpack_range(range, 0, self.len);
-        self.get_range_rec(l, r, 0, 0, self.n)
+    fn pull(&mut self, k: usize) {
+        self.data[k] = T::op(&self.data[2 * k], &self.data[2 * k + 1]);
     }
 
-    fn update_range_rec(
-        &mut self,
-        l: usize,
-        r: usize,
-        k: usize,
-        f: &T::F,
-        seg_l: usize,
-        seg_r: usize,
-    ) {
-        self.eval(k);
-        if l <= seg_l && seg_r <= r {
-            self.lazy[k] = T::composition(&f, &self.lazy[k]);
-            self.eval(k);
-        } else if l < seg_r && seg_l < r {
-            self.update_range_rec(l, r, k * 2 + 1, f, seg_l, (seg_l + seg_r) / 2);
-            self.update_range_rec(l, r, k * 2 + 2, f, (seg_l + seg_r) / 2, seg_r);
-            self.data[k] = T::op(&self.data[2 * k + 1], &self.data[2 * k + 2]);
+    fn apply_node(&mut self, k: usize, f: &T::F) {
+        self.data[k] = T::mapping(f, &self.data[k]);
+        if k < self.n {
+            self.lazy[k] = T::composition(f, &self.lazy[k]);
         }
     }
 
-    pub fn update<R: RangeBounds<usize>>(&mut self, range: R, f: &T::F) {
-        let (l, r) = unpack_range(range, 0, self.len);
-        self.update_range_rec(l, r, 0, f, 0, self.n);
+    fn push(&mut self, k: usize) {
+        if self.lazy[k] == T::identity_map() {
+            return;
+        }
+        let f = std::mem::replace(&mut self.lazy[k], T::identity_map());
+        self.apply_node(2 * k, &f);
+        self.apply_node(2 * k + 1, &f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::segtree::monoids::RangeMinMonoid;
+
+    struct AddMin;
+    impl MapMonoid for AddMin {
+        type M = RangeMinMonoid<i64>;
+        type F = i64;
+        fn identity_map() -> Self::F {
+            0
+        }
+        fn mapping(f: &Self::F, x: &i64) -> i64 {
+            if *x == i64::MAX {
+                *x
+            } else {
+                x + f
+            }
+        }
+        fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+            f + g
+        }
+    }
+
+    #[test]
+    fn test_lazy_segment_tree() {
+        let mut naive = vec![5i64, 3, 8, 1, 9, 2, 7];
+        let mut seg = LazySegmentTree::<AddMin>::from_vec(&naive);
+        let ops = [(0, 3, 2), (2, 7, -4), (1, 2, 10), (4, 6, 3), (0, 7, 1)];
+        for &(l, r, x) in ops.iter() {
+            seg.update(l..r, &x);
+            naive[l..r].iter_mut().for_each(|v| *v += x);
+            for l in 0..naive.len() {
+                for r in l + 1..=naive.len() {
+                    assert_eq!(seg.get(l..r), *naive[l..r].iter().min().unwrap());
+                }
+                assert_eq!(seg.get_point(l), naive[l]);
+            }
+            assert_eq!(seg.all_prod(), *naive.iter().min().unwrap());
+        }
+
+        seg.set(3, -100);
+        naive[3] = -100;
+        assert_eq!(seg.get(..), -100);
+        assert_eq!(seg.max_right(0, |&m| m > -100), 3);
+        assert_eq!(seg.max_right(4, |&m| m > -100), 7);
+        assert_eq!(seg.min_left(7, |&m| m > -100), 4);
+        assert_eq!(seg.min_left(3, |&m| m > -100), 0);
+        for l in 0..=naive.len() {
+            for x in -10..15 {
+                let expected = (l..=naive.len())
+                    .take_while(|&r| naive[l..r].iter().all(|&v| v >= x))
+                    .last()
+                    .unwrap();
+                assert_eq!(seg.max_right(l, |&m| m >= x), expected);
+            }
+        }
     }
 }
@@ -1,3 +1,6 @@
+pub mod dynamic_segment_tree;
 pub mod lazy_segment_tree;
 pub mod monoids;
+pub mod persistent_segment_tree;
 pub mod segment_tree;
+pub mod segment_tree_beats;
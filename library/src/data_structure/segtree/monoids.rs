@@ -1,6 +1,6 @@
 use std::{fmt::Debug, marker::PhantomData};
 
-use crate::utils::integer::Integer;
+use crate::{math::euclidean::gcd, misc::modint::Modint, utils::integer::Integer};
 
 pub trait Monoid {
     type S: Copy + Debug;
@@ -56,3 +56,232 @@ impl<T: Integer> Monoid for RangeSumMonoid<T> {
         T::from_i32(0)
     }
 }
+
+/// 冪等なモノイド (op(a, a) = a)。SparseTable で重なりのある区間をまとめられる
+pub trait IdempotentMonoid: Monoid {}
+impl<T: Integer> IdempotentMonoid for RangeMinMonoid<T> {}
+impl<T: Integer> IdempotentMonoid for RangeMaxMonoid<T> {}
+impl<T: Integer> IdempotentMonoid for RangeGcdMonoid<T> {}
+
+pub struct RangeGcdMonoid<T>(PhantomData<T>);
+impl<T: Integer> Monoid for RangeGcdMonoid<T> {
+    type S = T;
+    fn op(a: Self::S, b: Self::S) -> Self::S {
+        gcd(a, b)
+    }
+    fn id() -> Self::S {
+        T::zero()
+    }
+}
+
+/// (区間和, 区間長) を持つモノイド。作用が区間長に依存する場合に使う
+/// 葉は (x, 1) で初期化する
+pub struct RangeSumWithLenMonoid<T>(PhantomData<T>);
+impl<T: Integer> Monoid for RangeSumWithLenMonoid<T> {
+    type S = (T, T);
+    fn op(a: Self::S, b: Self::S) -> Self::S {
+        (a.0 + b.0, a.1 + b.1)
+    }
+    fn id() -> Self::S {
+        (T::zero(), T::zero())
+    }
+}
+
+/// (区間和, 区間長) を Modint で持つモノイド
+/// 葉は (x, 1) で初期化する
+pub struct ModintSumWithLenMonoid<const MOD: u64>;
+impl<const MOD: u64> Monoid for ModintSumWithLenMonoid<MOD> {
+    type S = (Modint<MOD>, Modint<MOD>);
+    fn op(a: Self::S, b: Self::S) -> Self::S {
+        (a.0 + b.0, a.1 + b.1)
+    }
+    fn id() -> Self::S {
+        (Modint::new(0), Modint::new(0))
+    }
+}
+
+/// 区間加算・区間和
+pub struct RangeAddRangeSum<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeAddRangeSum<T> {
+    type M = RangeSumWithLenMonoid<T>;
+    type F = T;
+    fn identity_map() -> Self::F {
+        T::zero()
+    }
+    fn mapping(f: &Self::F, x: &(T, T)) -> (T, T) {
+        (x.0 + *f * x.1, x.1)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        *f + *g
+    }
+}
+
+/// 区間加算・区間最小値
+pub struct RangeAddRangeMin<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeAddRangeMin<T> {
+    type M = RangeMinMonoid<T>;
+    type F = T;
+    fn identity_map() -> Self::F {
+        T::zero()
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        // 単位元(番兵)には作用させない
+        if *x == T::MAX {
+            *x
+        } else {
+            *x + *f
+        }
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        *f + *g
+    }
+}
+
+/// 区間加算・区間最大値
+pub struct RangeAddRangeMax<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeAddRangeMax<T> {
+    type M = RangeMaxMonoid<T>;
+    type F = T;
+    fn identity_map() -> Self::F {
+        T::zero()
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        if *x == T::MIN {
+            *x
+        } else {
+            *x + *f
+        }
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        *f + *g
+    }
+}
+
+/// 区間更新・区間最小値
+/// 作用は Some(x) で x への更新、None で恒等写像を表す
+pub struct RangeUpdateRangeMin<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeUpdateRangeMin<T> {
+    type M = RangeMinMonoid<T>;
+    type F = Option<T>;
+    fn identity_map() -> Self::F {
+        None
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        f.unwrap_or(*x)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        f.or(*g)
+    }
+}
+
+/// 区間更新・区間最大値
+pub struct RangeUpdateRangeMax<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeUpdateRangeMax<T> {
+    type M = RangeMaxMonoid<T>;
+    type F = Option<T>;
+    fn identity_map() -> Self::F {
+        None
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        f.unwrap_or(*x)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        f.or(*g)
+    }
+}
+
+/// 区間更新・区間和
+pub struct RangeUpdateRangeSum<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeUpdateRangeSum<T> {
+    type M = RangeSumWithLenMonoid<T>;
+    type F = Option<T>;
+    fn identity_map() -> Self::F {
+        None
+    }
+    fn mapping(f: &Self::F, x: &(T, T)) -> (T, T) {
+        match f {
+            Some(v) => (*v * x.1, x.1),
+            None => *x,
+        }
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        f.or(*g)
+    }
+}
+
+/// 区間アフィン変換・区間和 (mod)
+/// 作用 (a, b) は x -> ax + b を表す
+pub struct RangeAffineRangeSum<const MOD: u64>;
+impl<const MOD: u64> MapMonoid for RangeAffineRangeSum<MOD> {
+    type M = ModintSumWithLenMonoid<MOD>;
+    type F = (Modint<MOD>, Modint<MOD>);
+    fn identity_map() -> Self::F {
+        (Modint::new(1), Modint::new(0))
+    }
+    fn mapping(f: &Self::F, x: &(Modint<MOD>, Modint<MOD>)) -> (Modint<MOD>, Modint<MOD>) {
+        (f.0 * x.0 + f.1 * x.1, x.1)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        (f.0 * g.0, f.0 * g.1 + f.1)
+    }
+}
+
+/// 区間chmin・区間最小値
+pub struct RangeChminRangeMin<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeChminRangeMin<T> {
+    type M = RangeMinMonoid<T>;
+    type F = T;
+    fn identity_map() -> Self::F {
+        T::MAX
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        (*f).min(*x)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        (*f).min(*g)
+    }
+}
+
+/// 区間chmax・区間最大値
+pub struct RangeChmaxRangeMax<T>(PhantomData<T>);
+impl<T: Integer> MapMonoid for RangeChmaxRangeMax<T> {
+    type M = RangeMaxMonoid<T>;
+    type F = T;
+    fn identity_map() -> Self::F {
+        T::MIN
+    }
+    fn mapping(f: &Self::F, x: &T) -> T {
+        (*f).max(*x)
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        (*f).max(*g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::segtree::lazy_segment_tree::LazySegmentTree;
+
+    #[test]
+    fn test_range_affine_range_sum() {
+        type Mint = Modint<998_244_353>;
+        let init: Vec<_> = (1..=5).map(|x| (Mint::new(x), Mint::new(1))).collect();
+        let mut seg = LazySegmentTree::<RangeAffineRangeSum<998_244_353>>::from_vec(&init);
+        // [1, 2, 3, 4, 5] -> [1, 5, 7, 9, 5] -> [1, 5, 7, 9, 5] * 2 + 1 (区間[0, 2))
+        seg.update(1..4, &(Mint::new(2), Mint::new(1)));
+        assert_eq!(seg.get(..).0, Mint::new(27));
+        seg.update(..2, &(Mint::new(2), Mint::new(1)));
+        assert_eq!(seg.get(0..2).0, Mint::new(3 + 11));
+        assert_eq!(seg.get(2..).0, Mint::new(21));
+    }
+
+    #[test]
+    fn test_range_update_range_sum() {
+        let mut seg = LazySegmentTree::<RangeUpdateRangeSum<i64>>::from_vec(&[(1, 1); 6]);
+        seg.update(1..5, &Some(-3));
+        seg.update(2..3, &Some(10));
+        assert_eq!(seg.get(..).0, 1 - 3 + 10 - 3 - 3 + 1);
+        assert_eq!(seg.get(2..4).0, 7);
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::RangeBounds;
+
+use crate::{
+    data_structure::segtree::monoids::{Monoid, RangeSumMonoid},
+    utils::{integer::Integer, versatility_functions::unpack_range},
+};
+
+/*
+ * 完全永続セグメント木
+ * set のたびに根から葉までのパス(O(log n)個)だけを複製し、新しい版を返す
+ * 古い版も変更されずに残るので、任意の版に対して取得・更新ができる
+*/
+
+/// 永続セグメント木の版を表すハンドル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version(usize);
+
+#[derive(Debug, Clone, Copy)]
+struct Node<S> {
+    val: S,
+    left: usize,
+    right: usize,
+}
+
+pub struct PersistentSegmentTree<T: Monoid> {
+    len: usize,
+    nodes: Vec<Node<T::S>>,
+    initial: Version,
+}
+
+impl<T: Monoid> PersistentSegmentTree<T> {
+    pub fn new(len: usize) -> Self {
+        Self::from_vec(&vec![T::id(); len])
+    }
+
+    pub fn from_vec(vec: &[T::S]) -> Self {
+        let mut seg = Self {
+            len: vec.len(),
+            nodes: Vec::with_capacity(2 * vec.len().max(1)),
+            initial: Version(0),
+        };
+        let root = if vec.is_empty() {
+            seg.new_node(T::id(), 0, 0)
+        } else {
+            seg.build(vec, 0, vec.len())
+        };
+        seg.initial = Version(root);
+        seg
+    }
+
+    /// 構築直後の版
+    pub fn initial(&self) -> Version {
+        self.initial
+    }
+
+    /// version の index 番目を value に変更した新しい版を返す
+    pub fn set(&mut self, version: Version, index: usize, value: T::S) -> Version {
+        assert!(index < self.len);
+        Version(self.set_rec(version.0, 0, self.len, index, value))
+    }
+
+    pub fn get<R: RangeBounds<usize>>(&self, version: Version, range: R) -> T::S {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        if l == r {
+            return T::id();
+        }
+        self.get_rec(version.0, 0, self.len, l, r)
+    }
+
+    pub fn get_point(&self, version: Version, index: usize) -> T::S {
+        assert!(index < self.len);
+        let (mut cur, mut l, mut r) = (version.0, 0, self.len);
+        while r - l > 1 {
+            let mid = (l + r) >> 1;
+            if index < mid {
+                cur = self.nodes[cur].left;
+                r = mid;
+            } else {
+                cur = self.nodes[cur].right;
+                l = mid;
+            }
+        }
+        self.nodes[cur].val
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn new_node(&mut self, val: T::S, left: usize, right: usize) -> usize {
+        self.nodes.push(Node { val, left, right });
+        self.nodes.len() - 1
+    }
+
+    fn build(&mut self, vec: &[T::S], l: usize, r: usize) -> usize {
+        if r - l == 1 {
+            return self.new_node(vec[l], 0, 0);
+        }
+        let mid = (l + r) >> 1;
+        let left = self.build(vec, l, mid);
+        let right = self.build(vec, mid, r);
+        let val = T::op(self.nodes[left].val, self.nodes[right].val);
+        self.new_node(val, left, right)
+    }
+
+    fn set_rec(&mut self, cur: usize, l: usize, r: usize, index: usize, value: T::S) -> usize {
+        if r - l == 1 {
+            return self.new_node(value, 0, 0);
+        }
+        let mid = (l + r) >> 1;
+        let Node { left, right, .. } = self.nodes[cur];
+        let (left, right) = if index < mid {
+            (self.set_rec(left, l, mid, index, value), right)
+        } else {
+            (left, self.set_rec(right, mid, r, index, value))
+        };
+        let val = T::op(self.nodes[left].val, self.nodes[right].val);
+        self.new_node(val, left, right)
+    }
+
+    fn get_rec(&self, cur: usize, seg_l: usize, seg_r: usize, q_l: usize, q_r: usize) -> T::S {
+        if seg_r <= q_l || q_r <= seg_l {
+            return T::id();
+        }
+        if q_l <= seg_l && seg_r <= q_r {
+            return self.nodes[cur].val;
+        }
+        let mid = (seg_l + seg_r) >> 1;
+        let left = self.get_rec(self.nodes[cur].left, seg_l, mid, q_l, q_r);
+        let right = self.get_rec(self.nodes[cur].right, mid, seg_r, q_l, q_r);
+        T::op(left, right)
+    }
+}
+
+impl<T: Integer> PersistentSegmentTree<RangeSumMonoid<T>> {
+    /// 各添字の値を (upper の値) - (lower の値) とみたときに、
+    /// 先頭からの累積和が k を超える最小の添字を返す。存在しなければ None
+    ///
+    /// 座標圧縮した値ごとの出現回数を版として積み上げておけば、
+    /// kth(ver[l], ver[r], k) で区間 [l, r) の k 番目 (0-indexed) に小さい値の圧縮後の添字が得られる
+    pub fn kth(&self, lower: Version, upper: Version, k: T) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let (mut lo, mut hi) = (lower.0, upper.0);
+        if self.nodes[hi].val - self.nodes[lo].val <= k {
+            return None;
+        }
+        let mut k = k;
+        let (mut l, mut r) = (0, self.len);
+        while r - l > 1 {
+            let mid = (l + r) >> 1;
+            let left_cnt =
+                self.nodes[self.nodes[hi].left].val - self.nodes[self.nodes[lo].left].val;
+            if k < left_cnt {
+                lo = self.nodes[lo].left;
+                hi = self.nodes[hi].left;
+                r = mid;
+            } else {
+                k -= left_cnt;
+                lo = self.nodes[lo].right;
+                hi = self.nodes[hi].right;
+                l = mid;
+            }
+        }
+        Some(l)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistent_segment_tree() {
+        let mut seg = PersistentSegmentTree::<RangeSumMonoid<i64>>::from_vec(&[1, 2, 3, 4, 5]);
+        let v0 = seg.initial();
+        let v1 = seg.set(v0, 2, 10);
+        let v2 = seg.set(v1, 0, -1);
+        let v3 = seg.set(v0, 4, 0);
+        assert_eq!(seg.get(v0, ..), 15);
+        assert_eq!(seg.get(v1, ..), 22);
+        assert_eq!(seg.get(v2, ..), 20);
+        assert_eq!(seg.get(v3, ..), 10);
+        assert_eq!(seg.get(v2, 1..3), 12);
+        assert_eq!(seg.get_point(v1, 0), 1);
+        assert_eq!(seg.get_point(v2, 0), -1);
+    }
+
+    #[test]
+    fn test_kth_smallest() {
+        // 区間 [l, r) の k 番目に小さい値
+        let a = [5usize, 1, 4, 2, 3, 0, 2];
+        let mut seg = PersistentSegmentTree::<RangeSumMonoid<usize>>::new(6);
+        let mut versions = vec![seg.initial()];
+        for &x in a.iter() {
+            let last = *versions.last().unwrap();
+            let cnt = seg.get_point(last, x);
+            versions.push(seg.set(last, x, cnt + 1));
+        }
+        for l in 0..a.len() {
+            for r in l + 1..=a.len() {
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort();
+                for (k, &x) in sorted.iter().enumerate() {
+                    assert_eq!(seg.kth(versions[l], versions[r], k), Some(x));
+                }
+                assert_eq!(seg.kth(versions[l], versions[r], r - l), None);
+            }
+        }
+    }
+}
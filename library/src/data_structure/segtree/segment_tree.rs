@@ -70,6 +70,120 @@ impl<T: Monoid> SegmentTree<T> {
         let right = self.get_rec((cur << 1) + 1, mid, seg_r, q_l, q_r);
         T::op(left, right)
     }
+
+    /// pred(op(a[l..r])) が true となる最大の r を返す
+    /// pred は単調であり、pred(id) = true であることを要求する
+    /// O(log n)
+    pub fn max_right<F>(&self, l: usize, pred: F) -> usize
+    where
+        F: Fn(T::S) -> bool,
+    {
+        assert!(l <= self.len);
+        assert!(pred(T::id()));
+        if l == self.len {
+            return self.len;
+        }
+        let mut cur = l + self.n;
+        let mut acc = T::id();
+        loop {
+            while cur & 1 == 0 {
+                cur >>= 1;
+            }
+            if !pred(T::op(acc, self.data[cur])) {
+                // 葉に降りながら境界を探す
+                while cur < self.n {
+                    cur <<= 1;
+                    let nxt = T::op(acc, self.data[cur]);
+                    if pred(nxt) {
+                        acc = nxt;
+                        cur += 1;
+                    }
+                }
+                return (cur - self.n).min(self.len);
+            }
+            acc = T::op(acc, self.data[cur]);
+            cur += 1;
+            if cur & cur.wrapping_neg() == cur {
+                break;
+            }
+        }
+        self.len
+    }
+
+    /// pred(op(a[l..r])) が true となる最小の l を返す
+    /// pred は単調であり、pred(id) = true であることを要求する
+    /// O(log n)
+    pub fn min_left<F>(&self, r: usize, pred: F) -> usize
+    where
+        F: Fn(T::S) -> bool,
+    {
+        assert!(r <= self.len);
+        assert!(pred(T::id()));
+        if r == 0 {
+            return 0;
+        }
+        let mut cur = r + self.n;
+        let mut acc = T::id();
+        loop {
+            cur -= 1;
+            while cur > 1 && cur % 2 == 1 {
+                cur >>= 1;
+            }
+            if !pred(T::op(self.data[cur], acc)) {
+                while cur < self.n {
+                    cur = (cur << 1) + 1;
+                    let nxt = T::op(self.data[cur], acc);
+                    if pred(nxt) {
+                        acc = nxt;
+                        cur -= 1;
+                    }
+                }
+                return cur + 1 - self.n;
+            }
+            acc = T::op(self.data[cur], acc);
+            if cur & cur.wrapping_neg() == cur {
+                break;
+            }
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::segtree::monoids::{RangeMinMonoid, RangeSumMonoid};
+
+    #[test]
+    fn test_max_right_min_left() {
+        let v = vec![3u64, 1, 4, 1, 5, 9, 2, 6];
+        let seg = SegmentTree::<RangeSumMonoid<u64>>::from_vec(&v);
+        for l in 0..=v.len() {
+            for x in 0..30 {
+                let naive = (l..=v.len())
+                    .take_while(|&r| v[l..r].iter().sum::<u64>() <= x)
+                    .last()
+                    .unwrap();
+                assert_eq!(seg.max_right(l, |s| s <= x), naive);
+            }
+        }
+        for r in 0..=v.len() {
+            for x in 0..30 {
+                let naive = (0..=r)
+                    .rev()
+                    .take_while(|&l| v[l..r].iter().sum::<u64>() <= x)
+                    .last()
+                    .unwrap();
+                assert_eq!(seg.min_left(r, |s| s <= x), naive);
+            }
+        }
+
+        let seg = SegmentTree::<RangeMinMonoid<i32>>::from_vec(&vec![5, 3, 7, 2, 8]);
+        assert_eq!(seg.max_right(0, |m| m >= 3), 3);
+        assert_eq!(seg.max_right(4, |m| m >= 3), 5);
+        assert_eq!(seg.min_left(5, |m| m >= 3), 4);
+        assert_eq!(seg.min_left(3, |m| m >= 3), 0);
+    }
 }
 
 // impl<T> Debug for SegmentTree<T>
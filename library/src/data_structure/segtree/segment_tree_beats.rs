@@ -0,0 +1,372 @@
+use std::{
+    cmp::Ordering,
+    ops::{Neg, RangeBounds},
+};
+
+use crate::utils::{integer::Integer, versatility_functions::unpack_range};
+
+/*
+ * Segment Tree Beats (Ji driver segment tree)
+ * 区間chmin / 区間chmax / 区間加算 と 区間和 / 区間最小値 / 区間最大値 を
+ * ならしO(log^2 n)で処理する
+ * 作用の途中で差 x - max などが負になるので、T は符号付き整数に限る (Neg で制約する)
+ *
+ * 各ノードは最大値・第二最大値・最大値の個数(最小値側も同様)を持ち、
+ * chmin(x) は「第二最大値 < x < 最大値」となるノードでのみ打ち切って作用させる
+ *
+ * MapMonoid の mapping は常に成功する前提なので、
+ * 「作用できなければ子に降りて再計算する」という beats の処理は LazySegmentTree に載せられない。
+ * そのため Monoid / MapMonoid は使わず、和・最小値・最大値に特化して実装している
+*/
+
+#[derive(Debug, Clone, Copy)]
+struct Node<T> {
+    sum: T,
+    max: T,
+    max2: T,
+    max_cnt: T,
+    min: T,
+    min2: T,
+    min_cnt: T,
+    len: T,
+    lazy_add: T,
+}
+
+impl<T: Integer> Node<T> {
+    fn empty() -> Self {
+        Self {
+            sum: T::zero(),
+            max: T::MIN,
+            max2: T::MIN,
+            max_cnt: T::zero(),
+            min: T::MAX,
+            min2: T::MAX,
+            min_cnt: T::zero(),
+            len: T::zero(),
+            lazy_add: T::zero(),
+        }
+    }
+
+    fn leaf(x: T) -> Self {
+        let one = T::from_i32(1);
+        Self {
+            sum: x,
+            max: x,
+            max2: T::MIN,
+            max_cnt: one,
+            min: x,
+            min2: T::MAX,
+            min_cnt: one,
+            len: one,
+            lazy_add: T::zero(),
+        }
+    }
+}
+
+/// 区間chmin / 区間chmax / 区間加算 / 区間代入 と 区間和 / 区間最小値 / 区間最大値
+pub struct SegmentTreeBeats<T: Integer + Neg<Output = T>> {
+    n: usize,
+    len: usize,
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Integer + Neg<Output = T>> SegmentTreeBeats<T> {
+    pub fn new(len: usize) -> Self {
+        Self::from_vec(&vec![T::zero(); len])
+    }
+
+    pub fn from_vec(vec: &[T]) -> Self {
+        let len = vec.len();
+        let mut n = 1;
+        while n < len {
+            n <<= 1;
+        }
+        let mut nodes = vec![Node::empty(); 2 * n];
+        for (i, &x) in vec.iter().enumerate() {
+            nodes[n + i] = Node::leaf(x);
+        }
+        let mut seg = Self { n, len, nodes };
+        for k in (1..n).rev() {
+            seg.pull(k);
+        }
+        seg
+    }
+
+    /// a[i] <- min(a[i], x)
+    pub fn range_chmin<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.chmin_rec(l, r, x, 1, 0, self.n);
+    }
+
+    /// a[i] <- max(a[i], x)
+    pub fn range_chmax<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.chmax_rec(l, r, x, 1, 0, self.n);
+    }
+
+    /// a[i] <- a[i] + x
+    pub fn range_add<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.add_rec(l, r, x, 1, 0, self.n);
+    }
+
+    /// a[i] <- x
+    pub fn range_update<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (l, r) = unpack_range(range, 0, self.len);
+        self.range_chmin(l..r, x);
+        self.range_chmax(l..r, x);
+    }
+
+    pub fn get_sum<R: RangeBounds<usize>>(&mut self, range: R) -> T {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.fold_rec(l, r, 1, 0, self.n, T::zero(), &|node| node.sum, &|a, b| {
+            a + b
+        })
+    }
+
+    pub fn get_min<R: RangeBounds<usize>>(&mut self, range: R) -> T {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.fold_rec(l, r, 1, 0, self.n, T::MAX, &|node| node.min, &|a, b| {
+            a.min(b)
+        })
+    }
+
+    pub fn get_max<R: RangeBounds<usize>>(&mut self, range: R) -> T {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        self.fold_rec(l, r, 1, 0, self.n, T::MIN, &|node| node.max, &|a, b| {
+            a.max(b)
+        })
+    }
+
+    pub fn get(&mut self, index: usize) -> T {
+        assert!(index < self.len);
+        self.get_sum(index..=index)
+    }
+
+    fn chmin_rec(&mut self, l: usize, r: usize, x: T, k: usize, seg_l: usize, seg_r: usize) {
+        if seg_r <= l || r <= seg_l || self.nodes[k].max <= x {
+            return;
+        }
+        if l <= seg_l && seg_r <= r && self.nodes[k].max2 < x {
+            self.apply_chmin(k, x);
+            return;
+        }
+        self.push(k);
+        let mid = (seg_l + seg_r) >> 1;
+        self.chmin_rec(l, r, x, k << 1, seg_l, mid);
+        self.chmin_rec(l, r, x, (k << 1) + 1, mid, seg_r);
+        self.pull(k);
+    }
+
+    fn chmax_rec(&mut self, l: usize, r: usize, x: T, k: usize, seg_l: usize, seg_r: usize) {
+        if seg_r <= l || r <= seg_l || self.nodes[k].min >= x {
+            return;
+        }
+        if l <= seg_l && seg_r <= r && self.nodes[k].min2 > x {
+            self.apply_chmax(k, x);
+            return;
+        }
+        self.push(k);
+        let mid = (seg_l + seg_r) >> 1;
+        self.chmax_rec(l, r, x, k << 1, seg_l, mid);
+        self.chmax_rec(l, r, x, (k << 1) + 1, mid, seg_r);
+        self.pull(k);
+    }
+
+    fn add_rec(&mut self, l: usize, r: usize, x: T, k: usize, seg_l: usize, seg_r: usize) {
+        if seg_r <= l || r <= seg_l {
+            return;
+        }
+        if l <= seg_l && seg_r <= r {
+            self.apply_add(k, x);
+            return;
+        }
+        self.push(k);
+        let mid = (seg_l + seg_r) >> 1;
+        self.add_rec(l, r, x, k << 1, seg_l, mid);
+        self.add_rec(l, r, x, (k << 1) + 1, mid, seg_r);
+        self.pull(k);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fold_rec<G, Op>(
+        &mut self,
+        l: usize,
+        r: usize,
+        k: usize,
+        seg_l: usize,
+        seg_r: usize,
+        id: T,
+        get: &G,
+        op: &Op,
+    ) -> T
+    where
+        G: Fn(&Node<T>) -> T,
+        Op: Fn(T, T) -> T,
+    {
+        if seg_r <= l || r <= seg_l {
+            return id;
+        }
+        if l <= seg_l && seg_r <= r {
+            return get(&self.nodes[k]);
+        }
+        self.push(k);
+        let mid = (seg_l + seg_r) >> 1;
+        let left = self.fold_rec(l, r, k << 1, seg_l, mid, id, get, op);
+        let right = self.fold_rec(l, r, (k << 1) + 1, mid, seg_r, id, get, op);
+        op(left, right)
+    }
+
+    // 第二最大値 < x < 最大値 を満たすノードに chmin を作用させる
+    fn apply_chmin(&mut self, k: usize, x: T) {
+        let node = &mut self.nodes[k];
+        node.sum += (x - node.max) * node.max_cnt;
+        if node.max == node.min {
+            node.min = x;
+        } else if node.max == node.min2 {
+            node.min2 = x;
+        }
+        node.max = x;
+    }
+
+    // 第二最小値 > x > 最小値 を満たすノードに chmax を作用させる
+    fn apply_chmax(&mut self, k: usize, x: T) {
+        let node = &mut self.nodes[k];
+        node.sum += (x - node.min) * node.min_cnt;
+        if node.min == node.max {
+            node.max = x;
+        } else if node.min == node.max2 {
+            node.max2 = x;
+        }
+        node.min = x;
+    }
+
+    fn apply_add(&mut self, k: usize, x: T) {
+        let node = &mut self.nodes[k];
+        if node.len == T::zero() {
+            return;
+        }
+        node.sum += x * node.len;
+        node.max += x;
+        if node.max2 != T::MIN {
+            node.max2 += x;
+        }
+        node.min += x;
+        if node.min2 != T::MAX {
+            node.min2 += x;
+        }
+        node.lazy_add += x;
+    }
+
+    fn push(&mut self, k: usize) {
+        let add = self.nodes[k].lazy_add;
+        if add != T::zero() {
+            self.apply_add(k << 1, add);
+            self.apply_add((k << 1) + 1, add);
+            self.nodes[k].lazy_add = T::zero();
+        }
+        let (max, min) = (self.nodes[k].max, self.nodes[k].min);
+        for c in [k << 1, (k << 1) + 1] {
+            if self.nodes[c].max > max {
+                self.apply_chmin(c, max);
+            }
+            if self.nodes[c].min < min {
+                self.apply_chmax(c, min);
+            }
+        }
+    }
+
+    fn pull(&mut self, k: usize) {
+        let left = self.nodes[k << 1];
+        let right = self.nodes[(k << 1) + 1];
+        let node = &mut self.nodes[k];
+        node.sum = left.sum + right.sum;
+        node.len = left.len + right.len;
+
+        match left.max.cmp(&right.max) {
+            Ordering::Less => {
+                node.max = right.max;
+                node.max_cnt = right.max_cnt;
+                node.max2 = left.max.max(right.max2);
+            }
+            Ordering::Greater => {
+                node.max = left.max;
+                node.max_cnt = left.max_cnt;
+                node.max2 = left.max2.max(right.max);
+            }
+            Ordering::Equal => {
+                node.max = left.max;
+                node.max_cnt = left.max_cnt + right.max_cnt;
+                node.max2 = left.max2.max(right.max2);
+            }
+        }
+
+        match left.min.cmp(&right.min) {
+            Ordering::Greater => {
+                node.min = right.min;
+                node.min_cnt = right.min_cnt;
+                node.min2 = left.min.min(right.min2);
+            }
+            Ordering::Less => {
+                node.min = left.min;
+                node.min_cnt = left.min_cnt;
+                node.min2 = left.min2.min(right.min);
+            }
+            Ordering::Equal => {
+                node.min = left.min;
+                node.min_cnt = left.min_cnt + right.min_cnt;
+                node.min2 = left.min2.min(right.min2);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::misc::rand::Pcg32;
+
+    #[test]
+    fn test_segment_tree_beats() {
+        let mut rng = Pcg32::with_seed(42, 7);
+        for n in [1, 2, 5, 13] {
+            let mut naive: Vec<i64> = (0..n).map(|_| rng.gen_range(0..100i64) - 50).collect();
+            let mut seg = SegmentTreeBeats::from_vec(&naive);
+            for _ in 0..500 {
+                let l = rng.gen_range(0..n);
+                let r = rng.gen_range(l + 1..=n);
+                let x = rng.gen_range(0..100i64) - 50;
+                match rng.gen_range(0..7u32) {
+                    0 => {
+                        seg.range_chmin(l..r, x);
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                    }
+                    1 => {
+                        seg.range_chmax(l..r, x);
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                    }
+                    2 => {
+                        seg.range_add(l..r, x);
+                        naive[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    3 => {
+                        seg.range_update(l..r, x);
+                        naive[l..r].iter_mut().for_each(|v| *v = x);
+                    }
+                    4 => assert_eq!(seg.get_sum(l..r), naive[l..r].iter().sum::<i64>()),
+                    5 => assert_eq!(seg.get_min(l..r), *naive[l..r].iter().min().unwrap()),
+                    _ => assert_eq!(seg.get_max(l..r), *naive[l..r].iter().max().unwrap()),
+                }
+            }
+            for (i, &v) in naive.iter().enumerate() {
+                assert_eq!(seg.get(i), v);
+            }
+        }
+    }
+}
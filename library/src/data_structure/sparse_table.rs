@@ -0,0 +1,138 @@
+use std::ops::RangeBounds;
+
+use crate::{
+    data_structure::segtree::monoids::{IdempotentMonoid, Monoid},
+    utils::versatility_functions::unpack_range,
+};
+
+/// 静的な列に対する区間演算を、前計算 O(n log n)・クエリ O(1) で答える
+/// 区間が重なってもよいように、冪等なモノイド (min, max, gcd など) のみ受け付ける
+pub struct SparseTable<T: IdempotentMonoid> {
+    table: Vec<Vec<T::S>>,
+}
+
+impl<T: IdempotentMonoid> SparseTable<T> {
+    pub fn new(vec: &[T::S]) -> Self {
+        let n = vec.len();
+        let mut table = vec![vec.to_vec()];
+        let mut k = 1;
+        while (1 << k) <= n {
+            let prev = &table[k - 1];
+            let half = 1 << (k - 1);
+            let cur = (0..=n - (1 << k))
+                .map(|i| T::op(prev[i], prev[i + half]))
+                .collect();
+            table.push(cur);
+            k += 1;
+        }
+        Self { table }
+    }
+
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> T::S {
+        let (l, r) = unpack_range(range, 0, self.table[0].len());
+        assert!(l <= r && r <= self.table[0].len());
+        if l == r {
+            return T::id();
+        }
+        let k = (r - l).ilog2() as usize;
+        T::op(self.table[k][l], self.table[k][r - (1 << k)])
+    }
+}
+
+/// 静的な列に対する区間演算を、前計算 O(n log n)・クエリ O(1) で答える
+/// 任意のモノイドを受け付ける (冪等性・可換性は不要)
+///
+/// 第 h 段では長さ 2^h のブロックの境界 m ごとに、
+/// [i, m) の総積 (i < m) と [m, i] の総積 (m <= i) を持つ
+pub struct DisjointSparseTable<T: Monoid> {
+    len: usize,
+    table: Vec<Vec<T::S>>,
+}
+
+impl<T: Monoid> DisjointSparseTable<T> {
+    pub fn new(vec: &[T::S]) -> Self {
+        let len = vec.len();
+        let levels = if len <= 1 {
+            1
+        } else {
+            (len - 1).ilog2() as usize + 1
+        };
+        let mut table = vec![vec.to_vec(); levels];
+        for (h, row) in table.iter_mut().enumerate() {
+            let size = 1 << h;
+            let mut mid = size;
+            while mid < len {
+                for i in (mid - size..mid - 1).rev() {
+                    row[i] = T::op(vec[i], row[i + 1]);
+                }
+                for i in mid + 1..(mid + size).min(len) {
+                    row[i] = T::op(row[i - 1], vec[i]);
+                }
+                mid += size << 1;
+            }
+        }
+        Self { len, table }
+    }
+
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> T::S {
+        let (l, r) = unpack_range(range, 0, self.len);
+        assert!(l <= r && r <= self.len);
+        if l == r {
+            return T::id();
+        }
+        let r = r - 1;
+        if l == r {
+            return self.table[0][l];
+        }
+        let h = (l ^ r).ilog2() as usize;
+        T::op(self.table[h][l], self.table[h][r])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::segtree::monoids::{RangeGcdMonoid, RangeMinMonoid};
+
+    struct Concat;
+    impl Monoid for Concat {
+        // (値, 桁数の10冪) として文字列連結を表す非可換モノイド
+        type S = (u64, u64);
+        fn op(a: Self::S, b: Self::S) -> Self::S {
+            (a.0 * b.1 + b.0, a.1 * b.1)
+        }
+        fn id() -> Self::S {
+            (0, 1)
+        }
+    }
+
+    #[test]
+    fn test_sparse_table() {
+        let v = [5i32, 3, 7, 9, 6, 4, 1, 2];
+        let st = SparseTable::<RangeMinMonoid<i32>>::new(&v);
+        for l in 0..v.len() {
+            for r in l + 1..=v.len() {
+                assert_eq!(st.get(l..r), *v[l..r].iter().min().unwrap());
+            }
+        }
+        let st = SparseTable::<RangeGcdMonoid<u64>>::new(&[12, 18, 24, 9]);
+        assert_eq!(st.get(..3), 6);
+        assert_eq!(st.get(1..), 3);
+    }
+
+    #[test]
+    fn test_disjoint_sparse_table() {
+        for n in 0..12 {
+            let v: Vec<_> = (0..n).map(|i| (i as u64 % 9 + 1, 10)).collect();
+            let dst = DisjointSparseTable::<Concat>::new(&v);
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = v[l..r]
+                        .iter()
+                        .fold(Concat::id(), |acc, &x| Concat::op(acc, x));
+                    assert_eq!(dst.get(l..r), expected);
+                }
+            }
+        }
+    }
+}
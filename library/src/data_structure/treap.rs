@@ -0,0 +1,589 @@
+use std::{
+    ops::RangeBounds,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    data_structure::segtree::monoids::{MapMonoid, Monoid},
+    utils::{integer::Integer, versatility_functions::unpack_range},
+};
+
+/*
+ * treap (乱択平衡二分探索木)
+ * OrderedTreap: キーの大小で並べる順序付き集合 / 多重集合
+ * ImplicitTreap: 位置をキーとする列。split / merge / 区間反転 / 区間作用 / 区間積
+ *
+ * ノードは Box で持つので、split で得た木をそのまま別の木として扱える
+ * OrderedTreap のノードはキーごとに重みを持てるようにしてあり、multiset::MultiSet もこれを使う
+*/
+
+// 全ての treap で共有するカウンタから splitmix64 で優先度を作る
+fn next_priority() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut z = COUNTER
+        .fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)
+        .wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+pub(crate) type OrderedLink<T, W> = Option<Box<OrderedNode<T, W>>>;
+
+// キーの大小で並べる treap のノード。OrderedTreap と MultiSet で共有する
+// weight はノード自身の重み (OrderedTreap では 1、MultiSet ではキーの個数)、size は部分木の重みの総和
+#[derive(Debug, Clone)]
+pub(crate) struct OrderedNode<T, W> {
+    pub(crate) key: T,
+    pub(crate) weight: W,
+    pub(crate) size: W,
+    priority: u64,
+    pub(crate) left: OrderedLink<T, W>,
+    pub(crate) right: OrderedLink<T, W>,
+}
+
+impl<T, W: Integer> OrderedNode<T, W> {
+    pub(crate) fn new(key: T, weight: W) -> Box<Self> {
+        Box::new(Self {
+            key,
+            weight,
+            size: weight,
+            priority: next_priority(),
+            left: None,
+            right: None,
+        })
+    }
+
+    pub(crate) fn size(t: &OrderedLink<T, W>) -> W {
+        t.as_ref().map_or(W::zero(), |n| n.size)
+    }
+
+    fn update(&mut self) {
+        self.size = Self::size(&self.left) + self.weight + Self::size(&self.right);
+    }
+
+    // pred(key) を満たすノード (先頭側) とそれ以外に分ける
+    pub(crate) fn split<P: Fn(&T) -> bool>(
+        t: OrderedLink<T, W>,
+        pred: &P,
+    ) -> (OrderedLink<T, W>, OrderedLink<T, W>) {
+        match t {
+            None => (None, None),
+            Some(mut node) => {
+                if pred(&node.key) {
+                    let (l, r) = Self::split(node.right.take(), pred);
+                    node.right = l;
+                    node.update();
+                    (Some(node), r)
+                } else {
+                    let (l, r) = Self::split(node.left.take(), pred);
+                    node.left = r;
+                    node.update();
+                    (l, Some(node))
+                }
+            }
+        }
+    }
+
+    pub(crate) fn merge(l: OrderedLink<T, W>, r: OrderedLink<T, W>) -> OrderedLink<T, W> {
+        match (l, r) {
+            (None, r) => r,
+            (l, None) => l,
+            (Some(mut l), Some(mut r)) => {
+                if l.priority > r.priority {
+                    l.right = Self::merge(l.right.take(), Some(r));
+                    l.update();
+                    Some(l)
+                } else {
+                    r.left = Self::merge(Some(l), r.left.take());
+                    r.update();
+                    Some(r)
+                }
+            }
+        }
+    }
+}
+
+/// 順序付き集合 / 多重集合
+/// insert / erase / nth / rank をいずれも期待 O(log n) で行う
+pub struct OrderedTreap<T> {
+    root: OrderedLink<T, usize>,
+    multi: bool,
+}
+
+impl<T: Ord> OrderedTreap<T> {
+    /// 重複を許さない集合
+    pub fn new_set() -> Self {
+        Self {
+            root: None,
+            multi: false,
+        }
+    }
+
+    /// 重複を許す多重集合
+    pub fn new_multiset() -> Self {
+        Self {
+            root: None,
+            multi: true,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        OrderedNode::size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// x を追加する。集合で既に x が存在する場合は何もせず false を返す
+    pub fn insert(&mut self, x: T) -> bool {
+        if !self.multi && self.contains(&x) {
+            return false;
+        }
+        let (l, r) = OrderedNode::split(self.root.take(), &|k: &T| *k < x);
+        let l = OrderedNode::merge(l, Some(OrderedNode::new(x, 1)));
+        self.root = OrderedNode::merge(l, r);
+        true
+    }
+
+    /// x を 1 つ削除する。存在しなければ false を返す
+    pub fn erase(&mut self, x: &T) -> bool {
+        let (l, r) = OrderedNode::split(self.root.take(), &|k: &T| k < x);
+        let (m, r) = OrderedNode::split(r, &|k: &T| k <= x);
+        let found = m.is_some();
+        let m = m.and_then(|mut node| OrderedNode::merge(node.left.take(), node.right.take()));
+        self.root = OrderedNode::merge(OrderedNode::merge(l, m), r);
+        found
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            cur = match x.cmp(&node.key) {
+                std::cmp::Ordering::Less => &node.left,
+                std::cmp::Ordering::Greater => &node.right,
+                std::cmp::Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+
+    /// x 未満の要素の個数
+    pub fn rank(&self, x: &T) -> usize {
+        let mut res = 0;
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            if node.key < *x {
+                res += OrderedNode::size(&node.left) + 1;
+                cur = &node.right;
+            } else {
+                cur = &node.left;
+            }
+        }
+        res
+    }
+
+    /// x と等しい要素の個数
+    pub fn count(&self, x: &T) -> usize {
+        let mut le = 0;
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            if node.key <= *x {
+                le += OrderedNode::size(&node.left) + 1;
+                cur = &node.right;
+            } else {
+                cur = &node.left;
+            }
+        }
+        le - self.rank(x)
+    }
+
+    /// 昇順で k 番目 (0-indexed) の要素
+    pub fn nth(&self, k: usize) -> Option<&T> {
+        let mut k = k;
+        let mut cur = &self.root;
+        while let Some(node) = cur {
+            let left = OrderedNode::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = &node.left,
+                std::cmp::Ordering::Equal => return Some(&node.key),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = &node.right;
+                }
+            }
+        }
+        None
+    }
+}
+
+type ImplicitLink<T> = Option<Box<ImplicitNode<T>>>;
+
+struct ImplicitNode<T: MapMonoid> {
+    val: <T::M as Monoid>::S,
+    // 部分木の左から右への積と、右から左への積
+    prod: <T::M as Monoid>::S,
+    rev_prod: <T::M as Monoid>::S,
+    // val / prod には適用済みで、子にはまだ伝播していない作用と反転
+    lazy: T::F,
+    rev: bool,
+    size: usize,
+    priority: u64,
+    left: ImplicitLink<T>,
+    right: ImplicitLink<T>,
+}
+
+impl<T: MapMonoid> ImplicitNode<T> {
+    fn new(val: <T::M as Monoid>::S) -> Box<Self> {
+        Box::new(Self {
+            val,
+            prod: val,
+            rev_prod: val,
+            lazy: T::identity_map(),
+            rev: false,
+            size: 1,
+            priority: next_priority(),
+            left: None,
+            right: None,
+        })
+    }
+
+    fn size(t: &ImplicitLink<T>) -> usize {
+        t.as_ref().map_or(0, |n| n.size)
+    }
+
+    fn prod(t: &ImplicitLink<T>) -> <T::M as Monoid>::S {
+        t.as_ref().map_or(T::id(), |n| n.prod)
+    }
+
+    fn rev_prod(t: &ImplicitLink<T>) -> <T::M as Monoid>::S {
+        t.as_ref().map_or(T::id(), |n| n.rev_prod)
+    }
+
+    fn apply(&mut self, f: &T::F) {
+        self.val = T::mapping(f, &self.val);
+        self.prod = T::mapping(f, &self.prod);
+        self.rev_prod = T::mapping(f, &self.rev_prod);
+        self.lazy = T::composition(f, &self.lazy);
+    }
+
+    fn toggle(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+        std::mem::swap(&mut self.prod, &mut self.rev_prod);
+        self.rev ^= true;
+    }
+
+    fn push(&mut self) {
+        if self.lazy != T::identity_map() {
+            let f = std::mem::replace(&mut self.lazy, T::identity_map());
+            for c in self.left.iter_mut().chain(self.right.iter_mut()) {
+                c.apply(&f);
+            }
+        }
+        if self.rev {
+            self.rev = false;
+            for c in self.left.iter_mut().chain(self.right.iter_mut()) {
+                c.toggle();
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        self.size = Self::size(&self.left) + 1 + Self::size(&self.right);
+        self.prod = T::op(
+            &T::op(&Self::prod(&self.left), &self.val),
+            &Self::prod(&self.right),
+        );
+        self.rev_prod = T::op(
+            &T::op(&Self::rev_prod(&self.right), &self.val),
+            &Self::rev_prod(&self.left),
+        );
+    }
+
+    // 先頭 k 個とそれ以外に分ける
+    fn split(t: ImplicitLink<T>, k: usize) -> (ImplicitLink<T>, ImplicitLink<T>) {
+        match t {
+            None => (None, None),
+            Some(mut node) => {
+                node.push();
+                let left = Self::size(&node.left);
+                if k <= left {
+                    let (l, r) = Self::split(node.left.take(), k);
+                    node.left = r;
+                    node.update();
+                    (l, Some(node))
+                } else {
+                    let (l, r) = Self::split(node.right.take(), k - left - 1);
+                    node.right = l;
+                    node.update();
+                    (Some(node), r)
+                }
+            }
+        }
+    }
+
+    fn merge(l: ImplicitLink<T>, r: ImplicitLink<T>) -> ImplicitLink<T> {
+        match (l, r) {
+            (None, r) => r,
+            (l, None) => l,
+            (Some(mut l), Some(mut r)) => {
+                if l.priority > r.priority {
+                    l.push();
+                    l.right = Self::merge(l.right.take(), Some(r));
+                    l.update();
+                    Some(l)
+                } else {
+                    r.push();
+                    r.left = Self::merge(Some(l), r.left.take());
+                    r.update();
+                    Some(r)
+                }
+            }
+        }
+    }
+
+    fn collect(t: &mut ImplicitLink<T>, res: &mut Vec<<T::M as Monoid>::S>) {
+        if let Some(node) = t {
+            node.push();
+            Self::collect(&mut node.left, res);
+            res.push(node.val);
+            Self::collect(&mut node.right, res);
+        }
+    }
+}
+
+/// 位置をキーとする列 (implicit treap)
+/// 挿入・削除・split・merge・区間反転・区間作用・区間積をいずれも期待 O(log n) で行う
+/// 区間反転があっても非可換なモノイドの積を正しく返す
+pub struct ImplicitTreap<T: MapMonoid> {
+    root: ImplicitLink<T>,
+}
+
+impl<T: MapMonoid> Default for ImplicitTreap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: MapMonoid> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn from_vec(vec: &[<T::M as Monoid>::S]) -> Self {
+        let root = vec.iter().fold(None, |acc, &x| {
+            ImplicitNode::merge(acc, Some(ImplicitNode::new(x)))
+        });
+        Self { root }
+    }
+
+    pub fn len(&self) -> usize {
+        ImplicitNode::size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// 位置 pos の直前に x を挿入する
+    pub fn insert(&mut self, pos: usize, x: <T::M as Monoid>::S) {
+        assert!(pos <= self.len());
+        let (l, r) = ImplicitNode::split(self.root.take(), pos);
+        let l = ImplicitNode::merge(l, Some(ImplicitNode::new(x)));
+        self.root = ImplicitNode::merge(l, r);
+    }
+
+    /// 位置 pos の要素を削除して返す
+    pub fn erase(&mut self, pos: usize) -> <T::M as Monoid>::S {
+        assert!(pos < self.len());
+        let (l, r) = ImplicitNode::split(self.root.take(), pos);
+        let (m, r) = ImplicitNode::split(r, 1);
+        self.root = ImplicitNode::merge(l, r);
+        m.unwrap().val
+    }
+
+    pub fn get(&mut self, pos: usize) -> <T::M as Monoid>::S {
+        assert!(pos < self.len());
+        self.fold(pos..=pos)
+    }
+
+    pub fn set(&mut self, pos: usize, x: <T::M as Monoid>::S) {
+        self.erase(pos);
+        self.insert(pos, x);
+    }
+
+    /// 先頭 k 個とそれ以外の 2 つの列に分ける
+    pub fn split(self, k: usize) -> (Self, Self) {
+        assert!(k <= self.len());
+        let (l, r) = ImplicitNode::split(self.root, k);
+        (Self { root: l }, Self { root: r })
+    }
+
+    /// self の後ろに other を連結する
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            root: ImplicitNode::merge(self.root, other.root),
+        }
+    }
+
+    pub fn fold<R: RangeBounds<usize>>(&mut self, range: R) -> <T::M as Monoid>::S {
+        let mut res = T::id();
+        self.with_range(range, |node| res = node.prod);
+        res
+    }
+
+    pub fn apply<R: RangeBounds<usize>>(&mut self, range: R, f: &T::F) {
+        self.with_range(range, |node| node.apply(f));
+    }
+
+    pub fn reverse<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.with_range(range, |node| node.toggle());
+    }
+
+    pub fn to_vec(&mut self) -> Vec<<T::M as Monoid>::S> {
+        let mut res = Vec::with_capacity(self.len());
+        ImplicitNode::collect(&mut self.root, &mut res);
+        res
+    }
+
+    // range に対応する部分木を切り出して f を適用し、元に戻す
+    fn with_range<R, G>(&mut self, range: R, f: G)
+    where
+        R: RangeBounds<usize>,
+        G: FnOnce(&mut ImplicitNode<T>),
+    {
+        let (l, r) = unpack_range(range, 0, self.len());
+        assert!(l <= r && r <= self.len());
+        let (a, rest) = ImplicitNode::split(self.root.take(), l);
+        let (mut b, c) = ImplicitNode::split(rest, r - l);
+        if let Some(node) = b.as_mut() {
+            f(node);
+        }
+        self.root = ImplicitNode::merge(ImplicitNode::merge(a, b), c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_structure::segtree::monoids::RangeAffineRangeSum,
+        misc::{modint::Modint, rand::Pcg32},
+    };
+
+    #[test]
+    fn test_ordered_treap() {
+        let mut set = OrderedTreap::new_set();
+        let mut multi = OrderedTreap::new_multiset();
+        for &x in [5, 1, 4, 1, 5, 9, 2, 6, 5].iter() {
+            set.insert(x);
+            multi.insert(x);
+        }
+        assert_eq!(set.len(), 6);
+        assert_eq!(multi.len(), 9);
+        assert!(!set.insert(9));
+        assert!(multi.insert(9));
+        let sorted: Vec<_> = (0..set.len()).map(|k| *set.nth(k).unwrap()).collect();
+        assert_eq!(sorted, vec![1, 2, 4, 5, 6, 9]);
+        assert_eq!(multi.nth(5), Some(&5));
+        assert_eq!(multi.nth(10), None);
+        assert_eq!(multi.rank(&5), 4);
+        assert_eq!(multi.count(&5), 3);
+        assert_eq!(set.count(&5), 1);
+
+        assert!(multi.erase(&5));
+        assert_eq!(multi.count(&5), 2);
+        assert!(!multi.erase(&3));
+        assert!(set.erase(&1));
+        assert!(!set.contains(&1));
+        assert_eq!(set.rank(&100), 5);
+    }
+
+    #[test]
+    fn test_implicit_treap() {
+        type Mint = Modint<998_244_353>;
+        type T = RangeAffineRangeSum<998_244_353>;
+        let mut rng = Pcg32::with_seed(3, 4);
+        let mut naive: Vec<Mint> = (0..20).map(Mint::new).collect();
+        let init: Vec<_> = naive.iter().map(|&x| (x, Mint::new(1))).collect();
+        let mut treap = ImplicitTreap::<T>::from_vec(&init);
+        for _ in 0..500 {
+            let n = naive.len();
+            let l = rng.gen_range(0..=n);
+            let r = rng.gen_range(l..=n);
+            match rng.gen_range(0..5u32) {
+                0 => {
+                    treap.reverse(l..r);
+                    naive[l..r].reverse();
+                }
+                1 => {
+                    let (a, b) = (
+                        Mint::new(rng.gen_range(1..10u64)),
+                        Mint::new(rng.gen_range(0..10u64)),
+                    );
+                    treap.apply(l..r, &(a, b));
+                    for x in naive[l..r].iter_mut() {
+                        *x = a * *x + b;
+                    }
+                }
+                2 => {
+                    let x = Mint::new(rng.gen_range(0..100u64));
+                    treap.insert(l, (x, Mint::new(1)));
+                    naive.insert(l, x);
+                }
+                3 if l < n => {
+                    assert_eq!(treap.erase(l).0, naive.remove(l));
+                }
+                _ => {
+                    let (a, b) = treap.split(l);
+                    assert_eq!(a.len(), l);
+                    treap = a.merge(b);
+                }
+            }
+            let l = rng.gen_range(0..=naive.len());
+            let r = rng.gen_range(l..=naive.len());
+            let expected = naive[l..r].iter().fold(Mint::new(0), |acc, &x| acc + x);
+            assert_eq!(treap.fold(l..r).0, expected);
+        }
+        let all: Vec<_> = treap.to_vec().into_iter().map(|x| x.0).collect();
+        assert_eq!(all, naive);
+    }
+
+    #[test]
+    fn test_implicit_treap_reverse_non_commutative() {
+        // 文字列の連結を (値, 10^桁数) で表す非可換なモノイド
+        struct Concat;
+        impl Monoid for Concat {
+            type S = (u64, u64);
+            fn op(a: Self::S, b: Self::S) -> Self::S {
+                (a.0 * b.1 + b.0, a.1 * b.1)
+            }
+            fn id() -> Self::S {
+                (0, 1)
+            }
+        }
+        struct NoMap;
+        impl MapMonoid for NoMap {
+            type M = Concat;
+            type F = ();
+            fn identity_map() {}
+            fn mapping(_: &(), x: &(u64, u64)) -> (u64, u64) {
+                *x
+            }
+            fn composition(_: &(), _: &()) {}
+        }
+
+        let v: Vec<_> = (1..=6).map(|d| (d, 10)).collect();
+        let mut treap = ImplicitTreap::<NoMap>::from_vec(&v);
+        assert_eq!(treap.fold(..).0, 123456);
+        treap.reverse(1..5);
+        assert_eq!(treap.fold(..).0, 154326);
+        assert_eq!(treap.fold(2..4).0, 43);
+        treap.reverse(..);
+        assert_eq!(treap.fold(..).0, 623451);
+        assert_eq!(treap.get(1), (2, 10));
+        treap.set(1, (9, 10));
+        assert_eq!(treap.fold(..3).0, 693);
+    }
+}
@@ -6,6 +6,7 @@ where
     vertex: Vec<usize>,
     data: Vec<Option<T>>,
     merge_op: F,
+    components: usize,
 }
 
 impl<T: Clone, F: Fn(&T, &T) -> T> UnionFind<T, F> {
@@ -14,6 +15,7 @@ impl<T: Clone, F: Fn(&T, &T) -> T> UnionFind<T, F> {
             vertex: vec![!1; size],
             data: vec![None; size],
             merge_op,
+            components: size,
         }
     }
 
@@ -73,6 +75,7 @@ impl<T: Clone, F: Fn(&T, &T) -> T> UnionFind<T, F> {
                 }
             };
         }
+        self.components -= 1;
 
         true
     }
@@ -86,4 +89,71 @@ impl<T: Clone, F: Fn(&T, &T) -> T> UnionFind<T, F> {
         let root = self.leader(u);
         self.data[root].as_ref()
     }
+
+    // 連結成分の個数
+    pub fn count_components(&self) -> usize {
+        self.components
+    }
+
+    // 各連結成分の代表元を昇順に返す
+    pub fn leaders(&self) -> Vec<usize> {
+        (0..self.vertex.len())
+            .filter(|&u| self.vertex[u] > self.vertex.len())
+            .collect()
+    }
+
+    // 連結成分ごとの頂点リストを返す
+    // 各リストは昇順で、リスト同士は最小の頂点の昇順に並ぶ
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.vertex.len();
+        let mut group_id = vec![None; n];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for u in 0..n {
+            let root = self.leader(u);
+            let id = *group_id[root].get_or_insert_with(|| {
+                groups.push(Vec::with_capacity(!self.vertex[root]));
+                groups.len() - 1
+            });
+            groups[id].push(u);
+        }
+        groups
+    }
+
+    // dataを持つ連結成分について (代表元, &data) を列挙する
+    pub fn iter_data(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        let n = self.vertex.len();
+        self.data
+            .iter()
+            .enumerate()
+            .filter(move |&(u, _)| self.vertex[u] > n)
+            .filter_map(|(u, d)| d.as_ref().map(|d| (u, d)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unionfind_groups() {
+        let mut uf = UnionFind::new(6, |a: &u32, b: &u32| *a.max(b));
+        for i in 0..6 {
+            uf.insert_data(i, i as u32);
+        }
+        assert_eq!(uf.count_components(), 6);
+        uf.merge(4, 1);
+        uf.merge(3, 5);
+        uf.merge(1, 5);
+        assert!(!uf.merge(4, 3));
+        assert_eq!(uf.count_components(), 3);
+        assert_eq!(uf.groups(), vec![vec![0], vec![1, 3, 4, 5], vec![2]]);
+
+        let leaders = uf.leaders();
+        assert_eq!(leaders.len(), 3);
+        let data: Vec<_> = uf.iter_data().map(|(l, &d)| (l, d)).collect();
+        assert_eq!(data.iter().map(|&(l, _)| l).collect::<Vec<_>>(), leaders);
+        let mut data: Vec<_> = data.into_iter().map(|(l, d)| (uf.size(l), d)).collect();
+        data.sort();
+        assert_eq!(data, vec![(1, 0), (1, 2), (4, 5)]);
+    }
 }
@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 
+use crate::graph::Graph;
+
 pub trait BfsHandler {
     type State;
     /// 現在の状態から遷移可能な状態の一覧を返す
@@ -46,3 +48,74 @@ pub fn bfs<H: BfsHandler>(handler: &mut H, starts: impl IntoIterator<Item = H::S
         }
     }
 }
+
+/// Graph 上の頂点を状態とする BfsHandler
+/// 探索後、dist に始点からの辺数、parent に BFS 木での親が入る
+pub struct GraphBfs<'a, E> {
+    graph: &'a Graph<E>,
+    pub dist: Vec<Option<usize>>,
+    pub parent: Vec<Option<usize>>,
+}
+
+impl<'a, E: Copy> GraphBfs<'a, E> {
+    pub fn new(graph: &'a Graph<E>) -> Self {
+        Self {
+            graph,
+            dist: vec![None; graph.len()],
+            parent: vec![None; graph.len()],
+        }
+    }
+}
+
+impl<E: Copy> BfsHandler for GraphBfs<'_, E> {
+    type State = usize;
+
+    // 未訪問の隣接頂点はこの直後に訪問済みにされるので、ここで親を決めておく
+    fn neighbors(&mut self, state: &usize) -> Vec<usize> {
+        let mut res = Vec::new();
+        for e in self.graph.neighbors(*state) {
+            if self.dist[e.to].is_none() {
+                self.parent[e.to] = Some(*state);
+                res.push(e.to);
+            }
+        }
+        res
+    }
+
+    // 親が決まっていない頂点は始点として扱う
+    fn mark_visited(&mut self, state: &usize) {
+        self.dist[*state] = Some(self.parent[*state].map_or(0, |p| self.dist[p].unwrap() + 1));
+    }
+
+    fn is_visited(&self, state: &usize) -> bool {
+        self.dist[*state].is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_bfs() {
+        let g = Graph::from_edges(5, false, &[(0, 1, ()), (1, 2, ()), (0, 3, ()), (3, 2, ())]);
+        let mut handler = GraphBfs::new(&g);
+        bfs(&mut handler, [0]);
+        assert_eq!(handler.dist, vec![Some(0), Some(1), Some(2), Some(1), None]);
+        assert_eq!(handler.parent, vec![None, Some(0), Some(1), Some(0), None]);
+    }
+
+    #[test]
+    fn test_graph_bfs_reuse() {
+        // 同じ handler で別の連結成分から探索し直す
+        let g = Graph::from_edges(5, false, &[(0, 1, ()), (2, 3, ()), (3, 4, ())]);
+        let mut handler = GraphBfs::new(&g);
+        bfs(&mut handler, [0]);
+        bfs(&mut handler, [2]);
+        assert_eq!(
+            handler.dist,
+            vec![Some(0), Some(1), Some(0), Some(1), Some(2)]
+        );
+        assert_eq!(handler.parent, vec![None, Some(0), None, Some(2), Some(3)]);
+    }
+}
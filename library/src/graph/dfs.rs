@@ -1,3 +1,5 @@
+use crate::graph::Graph;
+
 /// DFS の抽象化ライブラリ
 ///
 /// `DfsHandler` トレイトを実装した構造体を作り、`dfs()` に渡して利用する
@@ -68,3 +70,62 @@ pub fn dfs<H: DfsHandler>(handler: &mut H, starts: impl IntoIterator<Item = H::S
         }
     }
 }
+
+/// Graph 上の頂点を状態とする DfsHandler
+/// 探索後、pre_order / post_order に行きがけ順・帰りがけ順が入る
+pub struct GraphDfs<'a, E> {
+    graph: &'a Graph<E>,
+    visited: Vec<bool>,
+    pub pre_order: Vec<usize>,
+    pub post_order: Vec<usize>,
+}
+
+impl<'a, E: Copy> GraphDfs<'a, E> {
+    pub fn new(graph: &'a Graph<E>) -> Self {
+        Self {
+            graph,
+            visited: vec![false; graph.len()],
+            pre_order: Vec::new(),
+            post_order: Vec::new(),
+        }
+    }
+}
+
+impl<E: Copy> DfsHandler for GraphDfs<'_, E> {
+    type State = usize;
+
+    fn neighbors(&mut self, state: &usize) -> Vec<usize> {
+        self.graph.neighbors(*state).iter().map(|e| e.to).collect()
+    }
+
+    fn is_visited(&self, state: &usize) -> bool {
+        self.visited[*state]
+    }
+
+    fn mark_visited(&mut self, state: &usize) {
+        self.visited[*state] = true;
+    }
+
+    fn on_enter(&mut self, state: &usize) -> bool {
+        self.pre_order.push(*state);
+        true
+    }
+
+    fn on_leave(&mut self, state: &usize) {
+        self.post_order.push(*state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_dfs() {
+        let g = Graph::from_edges(5, true, &[(0, 1, ()), (1, 2, ()), (0, 3, ()), (3, 2, ())]);
+        let mut handler = GraphDfs::new(&g);
+        dfs(&mut handler, [0, 4]);
+        assert_eq!(handler.pre_order, vec![0, 1, 2, 3, 4]);
+        assert_eq!(handler.post_order, vec![2, 1, 3, 0, 4]);
+    }
+}
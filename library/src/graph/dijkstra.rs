@@ -1,6 +1,6 @@
 use std::{cmp::Reverse, collections::BinaryHeap};
 
-use crate::utils::integer::Integer;
+use crate::{graph::Graph, utils::integer::Integer};
 
 #[allow(dead_code)]
 pub struct Dijkstra<T> {
@@ -11,8 +11,22 @@ pub struct Dijkstra<T> {
 
 impl<T: Integer> Dijkstra<T> {
     pub fn new(start: usize, graph: &Vec<Vec<(usize, T)>>) -> Self {
+        Self::run(start, graph.len(), |pos| graph[pos].iter().copied())
+    }
+
+    pub fn from_graph(start: usize, graph: &Graph<T>) -> Self {
+        Self::run(start, graph.len(), |pos| {
+            graph.neighbors(pos).iter().map(|e| (e.to, e.weight))
+        })
+    }
+
+    // neighbors(pos) は pos から出る (行き先, 重み) を返す
+    fn run<I, F>(start: usize, v: usize, neighbors: F) -> Self
+    where
+        I: Iterator<Item = (usize, T)>,
+        F: Fn(usize) -> I,
+    {
         let inf = T::inf();
-        let v = graph.len();
         let mut distance = vec![inf; v];
         let mut from = vec![None; v];
         let mut pq = BinaryHeap::new();
@@ -30,7 +44,7 @@ impl<T: Integer> Dijkstra<T> {
                 break;
             }
 
-            for &(ni, w) in &graph[pos] {
+            for (ni, w) in neighbors(pos) {
                 if distance[ni] == inf {
                     pq.push(Reverse((w + cost, ni, pos)));
                 }
@@ -2,4 +2,7 @@ pub mod bfs;
 pub mod dfs;
 pub mod dijkstra;
 pub mod scc;
+pub mod structure;
 pub mod warshall_floyd;
+
+pub use structure::{Csr, Edge, Graph};
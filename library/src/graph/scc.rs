@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 
-use crate::utils::chlibs::ChLibs;
+use crate::{graph::Graph, utils::chlibs::ChLibs};
 
 pub struct Scc {
     graph: Vec<Vec<usize>>,
@@ -11,6 +11,10 @@ impl Scc {
         Self { graph }
     }
 
+    pub fn from_graph<E: Copy>(graph: &Graph<E>) -> Self {
+        Self::new(graph.to_unweighted_list())
+    }
+
     pub fn execute(&self) -> Vec<Vec<usize>> {
         let n = self.graph.len();
         let mut scc_ids = vec![None; n];
@@ -0,0 +1,219 @@
+/*
+ * 各グラフアルゴリズムで共通して使うグラフ
+ * 辺には追加順に 0, 1, 2, ... の id を振る。無向辺は 1 本として id を 1 つだけ持ち、
+ * 隣接リストには両向きの Edge が同じ id で入る
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge<E> {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub weight: E,
+}
+
+impl<E: Copy> Edge<E> {
+    fn rev(&self) -> Self {
+        Self {
+            id: self.id,
+            from: self.to,
+            to: self.from,
+            weight: self.weight,
+        }
+    }
+}
+
+/// 隣接リストで持つグラフ
+/// 重みのないグラフは E = () とする
+#[derive(Debug, Clone)]
+pub struct Graph<E> {
+    directed: bool,
+    // id 順の辺 (無向辺は追加したときの向きで持つ)
+    edges: Vec<Edge<E>>,
+    adj: Vec<Vec<Edge<E>>>,
+}
+
+impl<E: Copy> Graph<E> {
+    pub fn directed(n: usize) -> Self {
+        Self {
+            directed: true,
+            edges: Vec::new(),
+            adj: vec![Vec::new(); n],
+        }
+    }
+
+    pub fn undirected(n: usize) -> Self {
+        Self {
+            directed: false,
+            edges: Vec::new(),
+            adj: vec![Vec::new(); n],
+        }
+    }
+
+    /// (u, v, 重み) の列からグラフを作る
+    pub fn from_edges(n: usize, directed: bool, edges: &[(usize, usize, E)]) -> Self {
+        let mut graph = if directed {
+            Self::directed(n)
+        } else {
+            Self::undirected(n)
+        };
+        for &(u, v, w) in edges {
+            graph.add_edge(u, v, w);
+        }
+        graph
+    }
+
+    /// u から v への辺を追加し、その id を返す (無向グラフでは両向き)
+    pub fn add_edge(&mut self, u: usize, v: usize, weight: E) -> usize {
+        assert!(u < self.len() && v < self.len());
+        let id = self.edges.len();
+        let edge = Edge {
+            id,
+            from: u,
+            to: v,
+            weight,
+        };
+        self.edges.push(edge);
+        self.adj[u].push(edge);
+        if !self.directed {
+            self.adj[v].push(edge.rev());
+        }
+        id
+    }
+
+    /// 頂点数
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn edge(&self, id: usize) -> &Edge<E> {
+        &self.edges[id]
+    }
+
+    /// 全ての辺を id 順に返す
+    pub fn edges(&self) -> &[Edge<E>] {
+        &self.edges
+    }
+
+    /// u から出る辺
+    pub fn neighbors(&self, u: usize) -> &[Edge<E>] {
+        &self.adj[u]
+    }
+
+    /// 全ての辺の向きを反転したグラフ。辺の id は保たれる
+    pub fn reversed(&self) -> Self {
+        if !self.directed {
+            return self.clone();
+        }
+        let mut adj = vec![Vec::new(); self.len()];
+        let edges: Vec<_> = self.edges.iter().map(|e| e.rev()).collect();
+        for e in edges.iter() {
+            adj[e.from].push(*e);
+        }
+        Self {
+            directed: true,
+            edges,
+            adj,
+        }
+    }
+
+    /// CSR 形式に変換する
+    pub fn to_csr(&self) -> Csr<E> {
+        let mut start = Vec::with_capacity(self.len() + 1);
+        start.push(0);
+        let mut edges = Vec::new();
+        for adj in self.adj.iter() {
+            edges.extend_from_slice(adj);
+            start.push(edges.len());
+        }
+        Csr { start, edges }
+    }
+
+    /// Vec<Vec<(行き先, 重み)>> 形式に変換する
+    pub fn to_adjacency_list(&self) -> Vec<Vec<(usize, E)>> {
+        self.adj
+            .iter()
+            .map(|es| es.iter().map(|e| (e.to, e.weight)).collect())
+            .collect()
+    }
+
+    /// 重みを落として Vec<Vec<行き先>> 形式に変換する
+    pub fn to_unweighted_list(&self) -> Vec<Vec<usize>> {
+        self.adj
+            .iter()
+            .map(|es| es.iter().map(|e| e.to).collect())
+            .collect()
+    }
+}
+
+/// 辺を始点ごとに 1 本の配列へ詰めたグラフ
+/// 構築後は変更できないが、隣接リストよりキャッシュ効率がよい
+#[derive(Debug, Clone)]
+pub struct Csr<E> {
+    start: Vec<usize>,
+    edges: Vec<Edge<E>>,
+}
+
+impl<E> Csr<E> {
+    /// 頂点数
+    pub fn len(&self) -> usize {
+        self.start.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// u から出る辺
+    pub fn neighbors(&self, u: usize) -> &[Edge<E>] {
+        &self.edges[self.start[u]..self.start[u + 1]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        let mut g = Graph::directed(4);
+        assert_eq!(g.add_edge(0, 1, 5), 0);
+        assert_eq!(g.add_edge(0, 2, 3), 1);
+        assert_eq!(g.add_edge(2, 1, 1), 2);
+        assert_eq!(g.add_edge(1, 3, 2), 3);
+        assert_eq!(g.edge_count(), 4);
+        assert_eq!(g.to_adjacency_list()[0], vec![(1, 5), (2, 3)]);
+        assert_eq!(g.edge(2).from, 2);
+
+        let r = g.reversed();
+        assert_eq!(
+            r.to_unweighted_list(),
+            vec![vec![], vec![0, 2], vec![0], vec![1]]
+        );
+        assert_eq!(r.neighbors(1)[1].id, 2);
+
+        let csr = g.to_csr();
+        assert_eq!(csr.len(), 4);
+        for u in 0..4 {
+            assert_eq!(csr.neighbors(u), g.neighbors(u));
+        }
+
+        let u = Graph::from_edges(3, false, &[(0, 1, ()), (1, 2, ())]);
+        assert_eq!(u.edge_count(), 2);
+        assert_eq!(u.to_unweighted_list(), vec![vec![1], vec![0, 2], vec![1]]);
+        assert_eq!(u.neighbors(2)[0].id, 1);
+        assert_eq!(u.neighbors(2)[0].to, 1);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::integer::Integer;
+use crate::{graph::Graph, utils::integer::Integer};
 
 pub trait WFelm<T>
 where
@@ -73,6 +73,10 @@ where
         Self { dist, op }
     }
 
+    pub fn from_graph(graph: &Graph<T>, op: O) -> Self {
+        Self::new(&graph.to_adjacency_list(), op)
+    }
+
     pub fn get(&self, from: usize, to: usize) -> T {
         self.dist[from][to]
     }
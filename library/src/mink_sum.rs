@@ -1,139 +1,240 @@
-// 小さい方からK個を管理し、和を答える
-// 基本的には問題に応じて書き換える想定
-#[derive(Debug)]
-pub struct MinK {
-    k: usize,
-    cnt: usize,
-    small_k_map: std::collections::BTreeMap<u64, u64>,
-    big_map: std::collections::BTreeMap<u64, u64>,
-    sum_small_k: u64,
+use std::collections::BTreeMap;
+
+use crate::utils::integer::Integer;
+
+// 多重集合を「小さい方から pos 個」と「残り」に分けて、それぞれの和を持つ
+#[derive(Debug, Clone)]
+struct Partition<T> {
+    lower: BTreeMap<T, usize>,
+    upper: BTreeMap<T, usize>,
+    lower_len: usize,
+    lower_sum: T,
+    upper_sum: T,
 }
 
-impl MinK {
-    pub fn new(k: usize) -> Self {
-        assert_ne!(k, 0);
-        MinK {
-            k,
-            cnt: 0,
-            small_k_map: std::collections::BTreeMap::new(),
-            big_map: std::collections::BTreeMap::new(),
-            sum_small_k: 0,
+impl<T: Integer> Partition<T> {
+    fn new() -> Self {
+        Self {
+            lower: BTreeMap::new(),
+            upper: BTreeMap::new(),
+            lower_len: 0,
+            lower_sum: T::zero(),
+            upper_sum: T::zero(),
         }
     }
 
-    pub fn add(&mut self, x: u64) {
-        *self.small_k_map.entry(x).or_insert(0) += 1;
-        self.cnt += 1;
-        self.sum_small_k += x;
-
-        if self.cnt > self.k {
-            let (&key, _) = self.small_k_map.last_key_value().unwrap();
-            // 大きい方から管理したい場合は↓を使う
-            // let (&key, _) = self.small_k_map.first_key_value().unwrap();
-            remove_from_map(&mut self.small_k_map, key);
-            self.cnt -= 1;
-            *self.big_map.entry(key).or_insert(0) += 1;
-
-            self.sum_small_k -= key;
+    fn add(&mut self, x: T) {
+        match self.lower.last_key_value() {
+            Some((&max, _)) if x <= max => {
+                *self.lower.entry(x).or_insert(0) += 1;
+                self.lower_len += 1;
+                self.lower_sum += x;
+            }
+            _ => {
+                *self.upper.entry(x).or_insert(0) += 1;
+                self.upper_sum += x;
+            }
         }
     }
 
-    pub fn remove(&mut self, x: u64) {
-        if self.big_map.contains_key(&x) {
-            remove_from_map(&mut self.big_map, x);
+    fn remove(&mut self, x: T) -> bool {
+        if remove_from_map(&mut self.lower, x) {
+            self.lower_len -= 1;
+            self.lower_sum -= x;
+            true
+        } else if remove_from_map(&mut self.upper, x) {
+            self.upper_sum -= x;
+            true
         } else {
-            remove_from_map(&mut self.small_k_map, x);
-            self.sum_small_k -= x;
-
-            if let Some((&key, _)) = self.big_map.first_key_value() {
-                // 大きい方からK個を管理したい場合は、last_key_value()に書き換える
-                *self.small_k_map.entry(key).or_insert(0) += 1;
-                remove_from_map(&mut self.big_map, key);
-                self.sum_small_k += key;
-            } else {
-                self.cnt -= 1;
-            }
+            false
         }
     }
 
-    pub fn ans(&self) -> u64 {
-        self.sum_small_k
-    }
-
-    pub fn size(&self) -> usize {
-        self.cnt
+    // lower の要素数が pos (要素が足りなければ全部) になるように要素を移す
+    fn rebalance(&mut self, pos: usize) {
+        while self.lower_len > pos {
+            let (&key, _) = self.lower.last_key_value().unwrap();
+            remove_from_map(&mut self.lower, key);
+            self.lower_len -= 1;
+            self.lower_sum -= key;
+            *self.upper.entry(key).or_insert(0) += 1;
+            self.upper_sum += key;
+        }
+        while self.lower_len < pos {
+            let Some((&key, _)) = self.upper.first_key_value() else {
+                break;
+            };
+            remove_from_map(&mut self.upper, key);
+            self.upper_sum -= key;
+            *self.lower.entry(key).or_insert(0) += 1;
+            self.lower_len += 1;
+            self.lower_sum += key;
+        }
     }
 }
 
-fn remove_from_map(map: &mut std::collections::BTreeMap<u64, u64>, key: u64) {
-    if map[&key] == 1 {
-        map.remove(&key);
-    } else {
-        map.insert(key, map[&key] - 1);
+fn remove_from_map<T: Ord>(map: &mut BTreeMap<T, usize>, key: T) -> bool {
+    match map.get_mut(&key) {
+        Some(cnt) if *cnt > 1 => *cnt -= 1,
+        Some(_) => {
+            map.remove(&key);
+        }
+        None => return false,
     }
+    true
 }
 
-#[derive(Debug)]
-pub struct MaxK {
+/// 多重集合を管理し、小さい方から K 個の和・大きい方から K 個の和・中央値を答える
+/// K は実行中に変更できる。各操作は O(log n) (set_k は変化量に比例)
+#[derive(Debug, Clone)]
+pub struct TopKSum<T> {
     k: usize,
-    cnt: usize,
-    small_k_map: std::collections::BTreeMap<u64, u64>,
-    big_map: std::collections::BTreeMap<u64, u64>,
-    sum_small_k: u64,
+    len: usize,
+    // 小さい方から k 個
+    min_side: Partition<T>,
+    // 小さい方から len - k 個 (upper が大きい方から k 個)
+    max_side: Partition<T>,
+    // 小さい方から (len + 1) / 2 個 (lower の最大値が中央値)
+    median: Partition<T>,
 }
 
-impl MaxK {
+impl<T: Integer> TopKSum<T> {
     pub fn new(k: usize) -> Self {
-        assert_ne!(k, 0);
         Self {
             k,
-            cnt: 0,
-            small_k_map: std::collections::BTreeMap::new(),
-            big_map: std::collections::BTreeMap::new(),
-            sum_small_k: 0,
+            len: 0,
+            min_side: Partition::new(),
+            max_side: Partition::new(),
+            median: Partition::new(),
         }
     }
 
-    pub fn add(&mut self, x: u64) {
-        *self.small_k_map.entry(x).or_insert(0) += 1;
-        self.cnt += 1;
-        self.sum_small_k += x;
-
-        if self.cnt > self.k {
-            // let (&key, _) = self.small_k_map.last_key_value().unwrap();
-            // 大きい方から管理したい場合は↓を使う
-            let (&key, _) = self.small_k_map.first_key_value().unwrap();
-            remove_from_map(&mut self.small_k_map, key);
-            self.cnt -= 1;
-            *self.big_map.entry(key).or_insert(0) += 1;
+    pub fn add(&mut self, x: T) {
+        self.len += 1;
+        self.min_side.add(x);
+        self.max_side.add(x);
+        self.median.add(x);
+        self.rebalance();
+    }
 
-            self.sum_small_k -= key;
+    /// x を 1 つ取り除く。x が存在しなければ false を返す
+    pub fn remove(&mut self, x: T) -> bool {
+        if !self.min_side.remove(x) {
+            return false;
         }
+        self.max_side.remove(x);
+        self.median.remove(x);
+        self.len -= 1;
+        self.rebalance();
+        true
     }
 
-    pub fn remove(&mut self, x: u64) {
-        if self.big_map.contains_key(&x) {
-            remove_from_map(&mut self.big_map, x);
-        } else {
-            remove_from_map(&mut self.small_k_map, x);
-            self.sum_small_k -= x;
-
-            if let Some((&key, _)) = self.big_map.last_key_value() {
-                // 大きい方からK個を管理したい場合は、last_key_value()に書き換える
-                *self.small_k_map.entry(key).or_insert(0) += 1;
-                remove_from_map(&mut self.big_map, key);
-                self.sum_small_k += key;
-            } else {
-                self.cnt -= 1;
-            }
-        }
+    pub fn set_k(&mut self, k: usize) {
+        self.k = k;
+        self.rebalance();
     }
 
-    pub fn ans(&self) -> u64 {
-        self.sum_small_k
+    pub fn k(&self) -> usize {
+        self.k
     }
 
-    pub fn size(&self) -> usize {
-        self.cnt
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 小さい方から K 個の和 (要素数が K 未満なら全体の和)
+    pub fn sum_min(&self) -> T {
+        self.min_side.lower_sum
+    }
+
+    /// 大きい方から K 個の和 (要素数が K 未満なら全体の和)
+    pub fn sum_max(&self) -> T {
+        self.max_side.upper_sum
+    }
+
+    /// 中央値。要素数が偶数の場合は小さい方を返す
+    pub fn median(&self) -> Option<T> {
+        self.median.lower.last_key_value().map(|(&x, _)| x)
+    }
+
+    fn rebalance(&mut self) {
+        self.min_side.rebalance(self.k);
+        self.max_side.rebalance(self.len.saturating_sub(self.k));
+        self.median.rebalance(self.len.div_ceil(2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k_sum() {
+        let mut top = TopKSum::new(2);
+        assert!(top.is_empty());
+        assert_eq!(top.median(), None);
+        top.add(5i64);
+        assert_eq!((top.sum_min(), top.sum_max()), (5, 5));
+        for &x in [3, 8, -1, 8].iter() {
+            top.add(x);
+        }
+        // [-1, 3, 5, 8, 8]
+        assert_eq!(top.len(), 5);
+        assert_eq!(top.sum_min(), 2);
+        assert_eq!(top.sum_max(), 16);
+        assert_eq!(top.median(), Some(5));
+
+        top.set_k(4);
+        assert_eq!(top.sum_min(), 15);
+        assert_eq!(top.sum_max(), 24);
+
+        assert!(top.remove(8));
+        assert!(!top.remove(4));
+        // [-1, 3, 5, 8]
+        assert_eq!(top.sum_min(), 15);
+        assert_eq!(top.median(), Some(3));
+        top.set_k(1);
+        assert_eq!((top.sum_min(), top.sum_max()), (-1, 8));
+        assert!(top.remove(-1));
+        assert_eq!((top.sum_min(), top.sum_max()), (3, 8));
+        assert_eq!(top.median(), Some(5));
+    }
+
+    #[test]
+    fn test_top_k_sum_random() {
+        use crate::misc::rand::Pcg32;
+        let mut rng = Pcg32::with_seed(5, 6);
+        let mut top = TopKSum::new(3);
+        let mut naive: Vec<u64> = vec![];
+        for _ in 0..1000 {
+            match rng.gen_range(0..4u32) {
+                0 => {
+                    let k = rng.gen_range(0..10usize);
+                    top.set_k(k);
+                }
+                1 if !naive.is_empty() => {
+                    let i = rng.gen_range(0..naive.len());
+                    assert!(top.remove(naive.swap_remove(i)));
+                }
+                _ => {
+                    let x = rng.gen_range(0..20u64);
+                    top.add(x);
+                    naive.push(x);
+                }
+            }
+            naive.sort();
+            let k = top.k().min(naive.len());
+            assert_eq!(top.sum_min(), naive[..k].iter().sum());
+            assert_eq!(top.sum_max(), naive[naive.len() - k..].iter().sum());
+            if naive.is_empty() {
+                assert_eq!(top.median(), None);
+            } else {
+                assert_eq!(top.median(), Some(naive[(naive.len() - 1) / 2]));
+            }
+        }
     }
 }